@@ -0,0 +1,65 @@
+use std::collections::HashMap;
+
+use crate::{block::ParseBlockError, Block};
+
+/// A table of custom names for [`Block`]s, such as `"roof"` for
+/// [`Block::DARK_OAK_WOOD_STAIRS`]
+///
+/// ```
+/// # use mcrs::{Block, BlockAliases};
+/// let mut aliases = BlockAliases::new();
+/// aliases.insert("roof", Block::DARK_OAK_WOOD_STAIRS);
+///
+/// assert_eq!(aliases.parse("Roof"), Ok(Block::DARK_OAK_WOOD_STAIRS));
+/// assert_eq!(aliases.parse("stone"), Ok(Block::STONE));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct BlockAliases {
+    aliases: HashMap<String, Block>,
+}
+
+impl BlockAliases {
+    /// Create an empty table
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an alias, ignoring case, returning the previous block for the alias
+    /// if any
+    pub fn insert(&mut self, alias: impl AsRef<str>, block: Block) -> Option<Block> {
+        self.aliases.insert(normalize(alias.as_ref()), block)
+    }
+
+    /// Remove an alias, returning its block if any
+    pub fn remove(&mut self, alias: &str) -> Option<Block> {
+        self.aliases.remove(&normalize(alias))
+    }
+
+    /// Get the block for an alias, ignoring case
+    pub fn get(&self, alias: &str) -> Option<Block> {
+        self.aliases.get(&normalize(alias)).copied()
+    }
+
+    /// Parse a block from an alias, falling back to [`Block::from_str`]
+    ///
+    /// Aliases take priority over block constant names
+    ///
+    /// [`Block::from_str`]: std::str::FromStr::from_str
+    pub fn parse(&self, string: &str) -> Result<Block, ParseBlockError> {
+        match self.get(string) {
+            Some(block) => Ok(block),
+            None => string.parse(),
+        }
+    }
+
+    /// Create an iterator over every alias and its block
+    pub fn iter(&self) -> impl Iterator<Item = (&str, Block)> {
+        self.aliases
+            .iter()
+            .map(|(alias, block)| (alias.as_str(), *block))
+    }
+}
+
+fn normalize(alias: &str) -> String {
+    alias.trim().to_lowercase()
+}
@@ -0,0 +1,152 @@
+use std::io;
+
+use crate::{command::Command, Block, Connection, Coordinate};
+
+/// Commands queued with [`Connection::batch`], which are sent together in one
+/// write by [`Batch::send`]
+///
+/// Block queries are sent along with the writes, and their responses are read
+/// once everything is sent. Nothing is sent if the batch is dropped
+///
+/// ```no_run
+/// # use mcrs::{Block, Connection};
+/// let mut mc = Connection::new().unwrap();
+/// let mut batch = mc.batch();
+/// for x in 0..1000 {
+///     batch.set_block([x, 80, 0], Block::GLASS);
+/// }
+/// batch.get_block([0, 79, 0]);
+/// let below = batch.send().unwrap();
+/// ```
+#[derive(Debug)]
+#[must_use = "commands are only sent by `send`"]
+pub struct Batch<'a> {
+    connection: &'a mut Connection,
+    operations: Vec<Operation>,
+}
+
+#[derive(Debug)]
+enum Operation {
+    Write(Coordinate, Coordinate, Block),
+    Chat(String),
+    Query(Coordinate),
+}
+
+impl<'a> Batch<'a> {
+    pub(crate) fn new(connection: &'a mut Connection) -> Self {
+        Self {
+            connection,
+            operations: Vec::new(),
+        }
+    }
+
+    /// Queue setting the block at a [`Coordinate`], like
+    /// [`Connection::set_block`]
+    pub fn set_block(&mut self, location: impl Into<Coordinate>, block: Block) -> &mut Self {
+        let location = location.into();
+        self.operations
+            .push(Operation::Write(location, location, block));
+        self
+    }
+
+    /// Queue setting a cuboid of blocks, like [`Connection::set_blocks`]
+    pub fn set_blocks(
+        &mut self,
+        a: impl Into<Coordinate>,
+        b: impl Into<Coordinate>,
+        block: Block,
+    ) -> &mut Self {
+        self.operations
+            .push(Operation::Write(a.into(), b.into(), block));
+        self
+    }
+
+    /// Queue a message to the in-game chat, like [`Connection::post_to_chat`]
+    pub fn post_to_chat(&mut self, message: impl Into<String>) -> &mut Self {
+        self.operations.push(Operation::Chat(message.into()));
+        self
+    }
+
+    /// Queue a query of the block at a [`Coordinate`], like
+    /// [`Connection::get_block`]
+    ///
+    /// The block is returned by [`Batch::send`], in the order queried
+    pub fn get_block(&mut self, location: impl Into<Coordinate>) -> &mut Self {
+        self.operations.push(Operation::Query(location.into()));
+        self
+    }
+
+    /// Returns the amount of queued commands
+    pub fn len(&self) -> usize {
+        self.operations.len()
+    }
+
+    /// Returns `true` if no commands are queued
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+
+    /// Send every queued command in one write, then read the response of
+    /// each queued query
+    ///
+    /// Returns the queried [`Block`]s in the order they were queued. If any
+    /// command is outside of the world limits, nothing is sent. Writes are
+    /// recorded for undo and edit logs as usual, but are not paced by the
+    /// configured [`Flow`](crate::Flow)
+    pub fn send(self) -> io::Result<Vec<Block>> {
+        let Self {
+            connection,
+            operations,
+        } = self;
+
+        for operation in &operations {
+            match *operation {
+                Operation::Write(a, b, _) => connection.check_in_world(a, b)?,
+                Operation::Query(location) => connection.check_in_world(location, location)?,
+                Operation::Chat(_) => (),
+            }
+        }
+        for operation in &operations {
+            if let Operation::Write(a, b, block) = *operation {
+                connection.record_undo(a, b, block)?;
+            }
+        }
+
+        let mut commands = Vec::with_capacity(operations.len());
+        let mut writes = Vec::new();
+        let mut queries = 0;
+        for operation in operations {
+            let command = match operation {
+                Operation::Write(a, b, block) => {
+                    writes.push((a, b, block));
+                    let command = if a == b {
+                        Command::new("world.setBlock").arg_coordinate(a)
+                    } else {
+                        Command::new("world.setBlocks")
+                            .arg_coordinate(a)
+                            .arg_coordinate(b)
+                    };
+                    command.arg_block(block)
+                }
+                Operation::Chat(message) => Command::new("chat.post").arg_string(message),
+                Operation::Query(location) => {
+                    queries += 1;
+                    connection.block_query(location)
+                }
+            };
+            commands.push(command);
+        }
+        connection.send_all(commands)?;
+        for (a, b, block) in writes {
+            connection.record_edit(a, b, block);
+            connection.invalidate_cache(a, b);
+        }
+
+        let mut blocks = Vec::with_capacity(queries);
+        for _ in 0..queries {
+            let response = connection.recv()?;
+            blocks.push(connection.parse_block_response(&response)?);
+        }
+        Ok(blocks)
+    }
+}
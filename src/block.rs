@@ -1,7 +1,9 @@
-use std::fmt;
+use std::{error, fmt, str::FromStr};
+
+use crate::block_state::BlockState;
 
 /// A Minecraft block, including `id` and `modifier`
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct Block {
     /// Block identifier. Eg. 'Andesite' has id `1` (`1:5`)
     pub id: i32,
@@ -14,8 +16,99 @@ impl Block {
     pub const fn new(id: i32, modifier: i32) -> Self {
         Self { id, modifier }
     }
+
+    /// Returns `true` if the block is a tree part or plant, such as logs,
+    /// leaves, saplings, flowers, grass, crops or vines
+    ///
+    /// Ignores `modifier`, as it also encodes orientation and growth stage
+    pub const fn is_vegetation(&self) -> bool {
+        matches!(
+            self.id,
+            6 | 17 | 18 | 31 | 32 | 37..=40 | 59 | 81 | 83 | 99 | 100 | 104..=106 | 111
+                | 127 | 141 | 142 | 161 | 162 | 175 | 207
+        )
+    }
+
+    /// Decode the modifier as a typed [`BlockState`], such as the facing of
+    /// stairs, or `None` if the state does not apply to the block
+    ///
+    /// [`BlockState`]: crate::block_state::BlockState
+    pub fn decode<T: BlockState>(&self) -> Option<T> {
+        T::decode(*self)
+    }
+
+    /// Returns `true` if the block falls when unsupported, such as sand and
+    /// gravel
+    pub const fn has_gravity(&self) -> bool {
+        matches!(self.id, 12 | 13 | 122 | 145 | 252)
+    }
+
+    /// Returns `true` if the block is flowing or still water or lava
+    pub const fn is_liquid(&self) -> bool {
+        matches!(self.id, 8..=11)
+    }
+
+    /// Returns `true` if light passes through the block, such as air, glass,
+    /// liquids, leaves, plants, torches and rails
+    pub const fn is_transparent(&self) -> bool {
+        matches!(
+            self.id,
+            0 | 6 | 8..=11 | 18 | 20 | 27 | 28 | 30..=32 | 37..=40 | 50 | 51 | 55 | 59 | 63
+                | 65 | 66 | 68..=70 | 72 | 75..=78 | 83 | 90 | 95 | 101 | 102 | 104..=106
+                | 111 | 131 | 132 | 141..=143 | 147 | 148 | 157 | 160 | 161 | 171 | 175 | 207
+        )
+    }
 }
 
+impl FromStr for Block {
+    type Err = ParseBlockError;
+
+    /// Parse a block from the name of a block constant, ignoring case and
+    /// treating spaces and hyphens as underscores (eg. `"polished andesite"`),
+    /// or from an `id` with an optional `modifier` (eg. `"1:6"` or `"1"`)
+    ///
+    /// Use [`BlockAliases`] to also accept custom names
+    ///
+    /// [`BlockAliases`]: crate::BlockAliases
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        let string = string.trim();
+        let error = || ParseBlockError {
+            input: string.to_string(),
+        };
+
+        if string.starts_with(|char: char| char.is_ascii_digit()) {
+            let (id, modifier) = string.split_once(':').unwrap_or((string, "0"));
+            let id = id.parse().map_err(|_| error())?;
+            let modifier = modifier.parse().map_err(|_| error())?;
+            return Ok(Self::new(id, modifier));
+        }
+
+        let name: String = string
+            .chars()
+            .map(|char| match char {
+                ' ' | '-' => '_',
+                _ => char.to_ascii_uppercase(),
+            })
+            .collect();
+        Self::from_name(&name).ok_or_else(error)
+    }
+}
+
+/// Error returned when parsing a [`Block`] fails
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseBlockError {
+    /// The string which could not be parsed
+    pub input: String,
+}
+
+impl fmt::Display for ParseBlockError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown block `{}`", self.input)
+    }
+}
+
+impl error::Error for ParseBlockError {}
+
 impl fmt::Display for Block {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self.get_name() {
@@ -40,6 +133,15 @@ macro_rules! blocks {
                 }
             }
 
+            /// Get the block with a non-standard name, as returned by
+            /// [`Block::get_name`]
+            pub fn from_name(name: &str) -> Option<Self> {
+                match name {
+                    $( stringify!($name) => Some(Self::$name), )*
+                    _ => None,
+                }
+            }
+
             $(
                 #[doc = concat!("Minecraft `", stringify!($name), "` block")]
                 pub const $name: Self = Self::new($id, $modifier);
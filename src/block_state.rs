@@ -0,0 +1,240 @@
+//! Each state decodes the modifier bits of a family of legacy blocks, such as
+//! the facing of stairs, with [`Block::decode`]
+//!
+//! ```
+//! # use mcrs::{block_state::{Facing, StairState}, Block};
+//! let stairs = Block::new(53, 6);
+//! let state: StairState = stairs.decode().unwrap();
+//! assert_eq!(state.facing, Facing::South);
+//! assert!(state.upside_down);
+//! ```
+
+use crate::{Axis, Block, Coordinate};
+
+/// A typed interpretation of the modifier of certain [`Block`]s
+pub trait BlockState: Sized {
+    /// Decode the state of a block, or `None` if the state does not apply to
+    /// the block
+    fn decode(block: Block) -> Option<Self>;
+}
+
+/// A horizontal direction
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Facing {
+    /// Towards negative `z`
+    North,
+    /// Towards positive `z`
+    South,
+    /// Towards positive `x`
+    East,
+    /// Towards negative `x`
+    West,
+}
+
+/// One of the 16 dye colors, used by wool, stained glass, hardened clay,
+/// carpet and concrete
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Color {
+    White,
+    Orange,
+    Magenta,
+    LightBlue,
+    Yellow,
+    Lime,
+    Pink,
+    Gray,
+    LightGray,
+    Cyan,
+    Purple,
+    Blue,
+    Brown,
+    Green,
+    Red,
+    Black,
+}
+
+/// Orientation of a log
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct LogState {
+    /// Axis the log runs along, or `None` if bark covers every side
+    pub axis: Option<Axis>,
+}
+
+/// Orientation of a stairs block
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct StairState {
+    /// Direction of the full-height back of the stairs
+    pub facing: Facing,
+    /// `true` if the stairs are attached to the top half of the block
+    pub upside_down: bool,
+}
+
+/// State of one half of a door
+///
+/// Each half only stores part of the state of the door, so the other half is
+/// needed to know it fully
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DoorState {
+    Lower {
+        /// Direction the door faces when closed
+        facing: Facing,
+        open: bool,
+    },
+    Upper {
+        /// `true` if the hinge is on the right, seen from the facing
+        /// direction
+        hinge_right: bool,
+        powered: bool,
+    },
+}
+
+impl From<Facing> for Coordinate {
+    /// Get the offset of the adjacent block in the direction
+    fn from(facing: Facing) -> Self {
+        match facing {
+            Facing::North => Coordinate::new(0, 0, -1),
+            Facing::South => Coordinate::new(0, 0, 1),
+            Facing::East => Coordinate::new(1, 0, 0),
+            Facing::West => Coordinate::new(-1, 0, 0),
+        }
+    }
+}
+
+impl Facing {
+    /// Get the direction pointing the other way
+    pub fn opposite(self) -> Self {
+        match self {
+            Facing::North => Facing::South,
+            Facing::South => Facing::North,
+            Facing::East => Facing::West,
+            Facing::West => Facing::East,
+        }
+    }
+}
+
+impl StairState {
+    /// Get the stairs block with the given `id` in this state
+    pub fn to_block(self, id: i32) -> Block {
+        let facing = match self.facing {
+            Facing::East => 0,
+            Facing::West => 1,
+            Facing::South => 2,
+            Facing::North => 3,
+        };
+        Block::new(id, facing | i32::from(self.upside_down) << 2)
+    }
+}
+
+/// Get the id of the stairs made of `material`, if there are any
+///
+/// Stairs blocks are their own material
+pub fn stairs_id(material: Block) -> Option<i32> {
+    let id = match (material.id, material.modifier) {
+        (53 | 67 | 108 | 109 | 114 | 128 | 134..=136 | 156 | 163 | 164 | 180 | 203, _) => {
+            material.id
+        }
+        (4, _) => 67,
+        (5, 0) => 53,
+        (5, 1) => 134,
+        (5, 2) => 135,
+        (5, 3) => 136,
+        (5, 4) => 163,
+        (5, 5) => 164,
+        (24, _) => 128,
+        (45, _) => 108,
+        (98, _) => 109,
+        (112, _) => 114,
+        (155, _) => 156,
+        (179, _) => 180,
+        (201, _) => 203,
+        _ => return None,
+    };
+    Some(id)
+}
+
+impl BlockState for Color {
+    fn decode(block: Block) -> Option<Self> {
+        if !matches!(block.id, 35 | 95 | 159 | 160 | 171 | 251 | 252) {
+            return None;
+        }
+        Some(match block.modifier {
+            0 => Self::White,
+            1 => Self::Orange,
+            2 => Self::Magenta,
+            3 => Self::LightBlue,
+            4 => Self::Yellow,
+            5 => Self::Lime,
+            6 => Self::Pink,
+            7 => Self::Gray,
+            8 => Self::LightGray,
+            9 => Self::Cyan,
+            10 => Self::Purple,
+            11 => Self::Blue,
+            12 => Self::Brown,
+            13 => Self::Green,
+            14 => Self::Red,
+            15 => Self::Black,
+            _ => return None,
+        })
+    }
+}
+
+impl BlockState for LogState {
+    fn decode(block: Block) -> Option<Self> {
+        if !matches!(block.id, 17 | 162) {
+            return None;
+        }
+        let axis = match block.modifier & 0b1100 {
+            0b0000 => Some(Axis::Y),
+            0b0100 => Some(Axis::X),
+            0b1000 => Some(Axis::Z),
+            _ => None,
+        };
+        Some(Self { axis })
+    }
+}
+
+impl BlockState for StairState {
+    fn decode(block: Block) -> Option<Self> {
+        if !matches!(
+            block.id,
+            53 | 67 | 108 | 109 | 114 | 128 | 134..=136 | 156 | 163 | 164 | 180 | 203
+        ) {
+            return None;
+        }
+        let facing = match block.modifier & 0b11 {
+            0 => Facing::East,
+            1 => Facing::West,
+            2 => Facing::South,
+            _ => Facing::North,
+        };
+        Some(Self {
+            facing,
+            upside_down: block.modifier & 0b100 != 0,
+        })
+    }
+}
+
+impl BlockState for DoorState {
+    fn decode(block: Block) -> Option<Self> {
+        if !matches!(block.id, 64 | 71 | 193..=197) {
+            return None;
+        }
+        if block.modifier & 0b1000 != 0 {
+            return Some(Self::Upper {
+                hinge_right: block.modifier & 0b01 != 0,
+                powered: block.modifier & 0b10 != 0,
+            });
+        }
+        let facing = match block.modifier & 0b11 {
+            0 => Facing::East,
+            1 => Facing::South,
+            2 => Facing::West,
+            _ => Facing::North,
+        };
+        Some(Self::Lower {
+            facing,
+            open: block.modifier & 0b100 != 0,
+        })
+    }
+}
@@ -0,0 +1,32 @@
+use crate::Block;
+
+/// Materials and dimensions of a bridge built with
+/// [`Connection::build_bridge`]
+///
+/// [`Connection::build_bridge`]: crate::Connection::build_bridge
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BridgeStyle {
+    /// Block used for the walkable deck
+    pub deck: Block,
+    /// Block placed along both edges of the deck, if any, each on an extra
+    /// row of deck
+    pub railing: Option<Block>,
+    /// Block used for the supporting pillars
+    pub pillar: Block,
+    /// Width of the deck in blocks, excluding railings
+    pub width: u32,
+    /// Distance between pillars in blocks. `0` means no pillars
+    pub pillar_spacing: u32,
+}
+
+impl Default for BridgeStyle {
+    fn default() -> Self {
+        Self {
+            deck: Block::OAK_WOOD_PLANK,
+            railing: Some(Block::OAK_FENCE),
+            pillar: Block::COBBLESTONE,
+            width: 3,
+            pillar_spacing: 8,
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use crate::{Block, Coordinate};
+
+/// Recent responses to block and height queries made through a
+/// [`Connection`], each kept until it expires or is written over
+///
+/// [`Connection`]: crate::Connection
+#[derive(Clone, Debug)]
+pub(crate) struct ReadCache {
+    ttl: Duration,
+    blocks: HashMap<Coordinate, (Block, Instant)>,
+    /// Keyed by (`x`, `z`)
+    heights: HashMap<(i32, i32), (i32, Instant)>,
+}
+
+impl ReadCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            blocks: HashMap::new(),
+            heights: HashMap::new(),
+        }
+    }
+
+    /// Get the cached [`Block`] at a [`Coordinate`], if not expired
+    pub fn block(&self, location: Coordinate) -> Option<Block> {
+        let (block, cached) = self.blocks.get(&location)?;
+        (cached.elapsed() < self.ttl).then_some(*block)
+    }
+
+    /// Get the cached height of a column, if not expired
+    pub fn height(&self, x: i32, z: i32) -> Option<i32> {
+        let (height, cached) = self.heights.get(&(x, z))?;
+        (cached.elapsed() < self.ttl).then_some(*height)
+    }
+
+    pub fn insert_block(&mut self, location: Coordinate, block: Block) {
+        self.blocks.insert(location, (block, Instant::now()));
+    }
+
+    pub fn insert_height(&mut self, x: i32, z: i32, height: i32) {
+        self.heights.insert((x, z), (height, Instant::now()));
+    }
+
+    /// Forget every block in the cuboid from `a` to `b`, and the height of
+    /// every column it covers
+    ///
+    /// Expired entries are also dropped, unless only a single block is
+    /// written
+    pub fn invalidate(&mut self, a: Coordinate, b: Coordinate) {
+        if a == b {
+            self.blocks.remove(&a);
+            self.heights.remove(&(a.x, a.z));
+            return;
+        }
+        let (min, max) = (a.min(b), a.max(b));
+        let ttl = self.ttl;
+        self.blocks.retain(|location, (_, cached)| {
+            let inside = (min.x..=max.x).contains(&location.x)
+                && (min.y..=max.y).contains(&location.y)
+                && (min.z..=max.z).contains(&location.z);
+            !inside && cached.elapsed() < ttl
+        });
+        self.heights.retain(|(x, z), (_, cached)| {
+            let inside = (min.x..=max.x).contains(x) && (min.z..=max.z).contains(z);
+            !inside && cached.elapsed() < ttl
+        });
+    }
+
+    pub fn clear(&mut self) {
+        self.blocks.clear();
+        self.heights.clear();
+    }
+}
@@ -1,16 +1,28 @@
-use std::fmt;
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    fmt,
+    io::{self, Read, Write},
+    mem,
+    ops::ControlFlow,
+    sync::Arc,
+};
 
-use crate::{Block, Coordinate};
+use crate::{coordinate::NEIGHBORS, Axis, Block, Coordinate};
 
 // Stores a 3D cuboid of [`Block`]s while preserving their location relative to
 // the base point they were gathered
 //
+// Cloning is cheap, as clones share the same block data until one is modified
+//
 /// [`Block`]: crate::Block
 #[derive(Clone)]
 pub struct Chunk {
-    list: Vec<Block>,
+    list: Arc<[Block]>,
     origin: Coordinate,
     size: Size,
+    /// **Relative** point placed at the target position when pasting
+    anchor: Option<Coordinate>,
+    metadata: BTreeMap<String, String>,
 }
 
 /// 3D size of a [`Chunk`]
@@ -30,9 +42,11 @@ impl Chunk {
         let a = a.into();
         let b = b.into();
         Self {
-            list,
+            list: list.into(),
             origin: a.min(b),
             size: a.size_between(b),
+            anchor: None,
+            metadata: BTreeMap::new(),
         }
     }
 
@@ -60,13 +74,1177 @@ impl Chunk {
         self.size
     }
 
+    /// Get the anchor point, **relative** to the origin, if set
+    ///
+    /// The anchor is the logical position of a structure (for example the
+    /// doorway of a house), which [`Connection::paste`] places at the target
+    /// position instead of the origin
+    ///
+    /// [`Connection::paste`]: crate::Connection::paste
+    pub fn anchor(&self) -> Option<Coordinate> {
+        self.anchor
+    }
+
+    /// Set or clear the anchor point, **relative** to the origin
+    ///
+    /// The anchor does not need to be within the chunk
+    pub fn set_anchor(&mut self, anchor: Option<Coordinate>) {
+        self.anchor = anchor;
+    }
+
+    /// Set the anchor point, **relative** to the origin
+    pub fn with_anchor(mut self, anchor: impl Into<Coordinate>) -> Self {
+        self.anchor = Some(anchor.into());
+        self
+    }
+
+    /// Get the **relative** [`Coordinate`] placed at the target position when
+    /// pasting: the anchor if set, otherwise the origin
+    pub fn pivot(&self) -> Coordinate {
+        self.anchor.unwrap_or(Coordinate::new(0, 0, 0))
+    }
+
+    /// Get the value of a metadata entry, if set
+    ///
+    /// Metadata is arbitrary cataloguing information such as `"author"` or
+    /// `"name"`, and is kept by [`Chunk::write_to`]. It is not sent to the
+    /// server
+    pub fn metadata(&self, key: &str) -> Option<&str> {
+        self.metadata.get(key).map(String::as_str)
+    }
+
+    /// Set a metadata entry, returning the previous value if any
+    pub fn set_metadata(
+        &mut self,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Option<String> {
+        self.metadata.insert(key.into(), value.into())
+    }
+
+    /// Remove a metadata entry, returning its value if any
+    pub fn remove_metadata(&mut self, key: &str) -> Option<String> {
+        self.metadata.remove(key)
+    }
+
+    /// Create an iterator over all metadata entries, sorted by key
+    pub fn metadata_entries(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.metadata
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+
     /// Create an iterator over the blocks in the chunk
-    pub fn iter(&self) -> Iter {
+    pub fn iter(&self) -> Iter<'_> {
         Iter::from(self)
     }
+
+    /// Call `visitor` with the **absolute** [`Coordinate`] and [`Block`] of
+    /// each block in the chunk, in the same order as [`Chunk::iter`], until it
+    /// returns [`ControlFlow::Break`]
+    ///
+    /// Returns the value of the break, if any
+    pub fn visit<B>(
+        &self,
+        mut visitor: impl FnMut(Coordinate, Block) -> ControlFlow<B>,
+    ) -> Option<B> {
+        for (index, block) in self.list.iter().enumerate() {
+            let coordinate = self.size.index_to_coordinate(index) + self.origin;
+            if let ControlFlow::Break(value) = visitor(coordinate, *block) {
+                return Some(value);
+            }
+        }
+        None
+    }
+
+    /// Get a horizontal slice of the chunk at the **relative** `y`-value,
+    /// which can be displayed as text
+    ///
+    /// See [`CrossSection`] for the characters used
+    pub fn cross_section(&self, y: i32) -> CrossSection<'_> {
+        CrossSection { chunk: self, y }
+    }
+
+    /// Create a copy of the chunk flipped along an [`Axis`], keeping the same
+    /// origin
+    ///
+    /// The anchor, if set, is flipped with the blocks
+    ///
+    /// Block modifiers are unchanged, so orientable blocks such as stairs keep
+    /// facing the same way
+    pub fn mirror(&self, axis: Axis) -> Chunk {
+        let size = self.size;
+        let list: Vec<Block> = (0..self.list.len())
+            .map(|index| {
+                let mut coordinate = size.index_to_coordinate(index);
+                match axis {
+                    Axis::X => coordinate.x = size.x as i32 - 1 - coordinate.x,
+                    Axis::Y => coordinate.y = size.y as i32 - 1 - coordinate.y,
+                    Axis::Z => coordinate.z = size.z as i32 - 1 - coordinate.z,
+                }
+                self.list[size.coordinate_to_index(coordinate)]
+            })
+            .collect();
+        let anchor = self.anchor.map(|mut anchor| {
+            match axis {
+                Axis::X => anchor.x = size.x as i32 - 1 - anchor.x,
+                Axis::Y => anchor.y = size.y as i32 - 1 - anchor.y,
+                Axis::Z => anchor.z = size.z as i32 - 1 - anchor.z,
+            }
+            anchor
+        });
+        Chunk {
+            list: list.into(),
+            origin: self.origin,
+            size,
+            anchor,
+            metadata: self.metadata.clone(),
+        }
+    }
+
+    /// Create a copy of the chunk rotated clockwise (seen from above) around
+    /// the vertical axis, by `quarter_turns` multiples of 90 degrees, keeping
+    /// the same origin
+    ///
+    /// Negative values rotate counter-clockwise. The anchor, if set, is rotated
+    /// with the blocks. Block modifiers are unchanged, so orientable blocks such
+    /// as stairs keep facing the same way
+    pub fn rotate(&self, quarter_turns: i32) -> Chunk {
+        let mut chunk = self.clone();
+        for _ in 0..quarter_turns.rem_euclid(4) {
+            let size = chunk.size;
+            let rotated_size = Size {
+                x: size.z,
+                y: size.y,
+                z: size.x,
+            };
+            let mut list = vec![Block::AIR; chunk.list.len()];
+            for (index, block) in chunk.list.iter().enumerate() {
+                let rotated = rotate_quarter(size.index_to_coordinate(index), size);
+                list[rotated_size.coordinate_to_index(rotated)] = *block;
+            }
+            chunk.list = list.into();
+            chunk.size = rotated_size;
+            chunk.anchor = chunk.anchor.map(|anchor| rotate_quarter(anchor, size));
+        }
+        chunk
+    }
+
+    /// Write the chunk in a compact binary format, readable with
+    /// [`Chunk::read_from`]
+    pub fn write_to(&self, mut writer: impl Write) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+        let origin = self.origin;
+        for value in [origin.x, origin.y, origin.z] {
+            writer.write_all(&value.to_le_bytes())?;
+        }
+        for value in [self.size.x, self.size.y, self.size.z] {
+            writer.write_all(&value.to_le_bytes())?;
+        }
+        match self.anchor {
+            Some(anchor) => {
+                writer.write_all(&[1])?;
+                for value in [anchor.x, anchor.y, anchor.z] {
+                    writer.write_all(&value.to_le_bytes())?;
+                }
+            }
+            None => writer.write_all(&[0])?,
+        }
+        writer.write_all(&(self.metadata.len() as u32).to_le_bytes())?;
+        for (key, value) in &self.metadata {
+            write_string(&mut writer, key)?;
+            write_string(&mut writer, value)?;
+        }
+        for block in self.list.iter() {
+            writer.write_all(&block.id.to_le_bytes())?;
+            writer.write_all(&block.modifier.to_le_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Write every block as CSV rows of `x,y,z,id,modifier`, with
+    /// **absolute** coordinates and a header row
+    pub fn write_csv(&self, mut writer: impl Write) -> io::Result<()> {
+        writeln!(writer, "x,y,z,id,modifier")?;
+        for item in self.iter() {
+            let position = item.position_absolute();
+            let block = item.block();
+            writeln!(
+                writer,
+                "{},{},{},{},{}",
+                position.x, position.y, position.z, block.id, block.modifier
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Write every block as newline-delimited JSON objects of the form
+    /// `{"x":0,"y":0,"z":0,"id":1,"modifier":0}`, with **absolute**
+    /// coordinates
+    ///
+    /// Each record is written as soon as it is formatted, so the output can be
+    /// piped into other tools without buffering the whole chunk as text
+    pub fn write_ndjson(&self, mut writer: impl Write) -> io::Result<()> {
+        for item in self.iter() {
+            let position = item.position_absolute();
+            let block = item.block();
+            writeln!(
+                writer,
+                r#"{{"x":{},"y":{},"z":{},"id":{},"modifier":{}}}"#,
+                position.x, position.y, position.z, block.id, block.modifier
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Read a chunk written with [`Chunk::write_to`]
+    pub fn read_from(mut reader: impl Read) -> io::Result<Chunk> {
+        let mut magic = [0; MAGIC.len()];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "not a chunk file",
+            ));
+        }
+        let reader = &mut reader;
+        let origin = read_coordinate(reader)?;
+        let size = Size {
+            x: read_i32(reader)? as u32,
+            y: read_i32(reader)? as u32,
+            z: read_i32(reader)? as u32,
+        };
+        let valid = |length: u32| (1..=i32::MAX as u32).contains(&length);
+        if !(valid(size.x) && valid(size.y) && valid(size.z)) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "chunk size is out of range",
+            ));
+        }
+        let mut has_anchor = [0];
+        reader.read_exact(&mut has_anchor)?;
+        let anchor = match has_anchor[0] {
+            0 => None,
+            _ => Some(read_coordinate(reader)?),
+        };
+        let mut metadata = BTreeMap::new();
+        for _ in 0..read_i32(reader)? {
+            metadata.insert(read_string(reader)?, read_string(reader)?);
+        }
+        let volume = size
+            .checked_volume()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "chunk is too large"))?;
+        let list = (0..volume)
+            .map(|_| Ok(Block::new(read_i32(reader)?, read_i32(reader)?)))
+            .collect::<io::Result<Vec<_>>>()?;
+        Ok(Chunk {
+            list: list.into(),
+            origin,
+            size,
+            anchor,
+            metadata,
+        })
+    }
+
+    /// Simulate blocks with gravity (see [`Block::has_gravity`]) falling
+    /// through air and liquids until they land, within the chunk
+    ///
+    /// The bottom of the chunk is treated as solid. Returns the **absolute**
+    /// [`Coordinate`]s of every changed block, which can be written back to
+    /// the world with [`Chunk::get`] and [`Connection::set_block`]
+    ///
+    /// [`Connection::set_block`]: crate::Connection::set_block
+    pub fn settle_gravity(&mut self) -> Vec<Coordinate> {
+        let size = self.size;
+        // Holding onto the original data makes the list below a fresh copy
+        let original = self.list.clone();
+        let list = Arc::make_mut(&mut self.list);
+        for x in 0..size.x as i32 {
+            for z in 0..size.z as i32 {
+                // Lowest position a falling block would land at
+                let mut landing = 0;
+                for y in 0..size.y as i32 {
+                    let index = size.coordinate_to_index([x, y, z]);
+                    let block = list[index];
+                    if block == Block::AIR || block.is_liquid() {
+                        continue;
+                    }
+                    if !block.has_gravity() {
+                        landing = y + 1;
+                        continue;
+                    }
+                    if landing < y {
+                        list[size.coordinate_to_index([x, landing, z])] = block;
+                        list[index] = Block::AIR;
+                    }
+                    landing += 1;
+                }
+            }
+        }
+
+        (0..list.len())
+            .filter(|index| list[*index] != original[*index])
+            .map(|index| size.index_to_coordinate(index) + self.origin)
+            .collect()
+    }
+
+    /// Estimate the sky light level of every block in the chunk, from `0`
+    /// (dark) to `15` (open sky)
+    ///
+    /// Light shines straight down each column from the top of the chunk until
+    /// an opaque block (see [`Block::is_transparent`]), then spreads sideways
+    /// and down through transparent blocks, losing one level per block. Block
+    /// light sources such as torches are ignored
+    pub fn estimate_sky_light(&self) -> LightMap {
+        let size = self.size;
+        let mut levels = vec![0u8; self.list.len()];
+        let mut queue = VecDeque::new();
+
+        for x in 0..size.x as i32 {
+            for z in 0..size.z as i32 {
+                for y in (0..size.y as i32).rev() {
+                    let index = size.coordinate_to_index([x, y, z]);
+                    if !self.list[index].is_transparent() {
+                        break;
+                    }
+                    levels[index] = 15;
+                    queue.push_back(index);
+                }
+            }
+        }
+
+        while let Some(index) = queue.pop_front() {
+            let level = levels[index];
+            if level <= 1 {
+                continue;
+            }
+            let coordinate = size.index_to_coordinate(index);
+            for offset in NEIGHBORS {
+                let neighbor = coordinate + offset;
+                if !size.contains(neighbor) {
+                    continue;
+                }
+                let neighbor = size.coordinate_to_index(neighbor);
+                if levels[neighbor] < level - 1 && self.list[neighbor].is_transparent() {
+                    levels[neighbor] = level - 1;
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+
+        LightMap {
+            levels,
+            origin: self.origin,
+            size,
+        }
+    }
+
+    /// Find the **absolute** [`Coordinate`]s of every space a mob could spawn
+    /// in: two blocks of air above an opaque floor, with an estimated light
+    /// level of `7` or less (see [`Chunk::estimate_sky_light`])
+    ///
+    /// The returned coordinate is the lower air block. Spaces at the top of the
+    /// chunk are not checked, as the block above is unknown
+    pub fn find_spawnable_spaces(&self) -> Vec<Coordinate> {
+        let light = self.estimate_sky_light();
+        let size = self.size;
+        let mut spaces = Vec::new();
+        for index in 0..self.list.len() {
+            let coordinate = size.index_to_coordinate(index);
+            let floor = coordinate - [0, 1, 0];
+            let head = coordinate + [0, 1, 0];
+            if !size.contains(floor) || !size.contains(head) {
+                continue;
+            }
+            let is_space = self.list[index] == Block::AIR
+                && self.list[size.coordinate_to_index(head)] == Block::AIR
+                && !self.list[size.coordinate_to_index(floor)].is_transparent();
+            if is_space && light.levels[index] <= 7 {
+                spaces.push(coordinate + self.origin);
+            }
+        }
+        spaces
+    }
+
+    /// Count the blocks needed to build the chunk, excluding air, most common
+    /// first
+    ///
+    /// Use [`format_item_count`] to display counts in stacks and shulker boxes
+    pub fn material_bill(&self) -> Vec<(Block, usize)> {
+        let mut counts: HashMap<Block, usize> = HashMap::new();
+        for block in self.list.iter().filter(|block| **block != Block::AIR) {
+            *counts.entry(*block).or_default() += 1;
+        }
+        let mut bill: Vec<_> = counts.into_iter().collect();
+        bill.sort_unstable_by(|a, b| {
+            (b.1.cmp(&a.1))
+                .then(a.0.id.cmp(&b.0.id))
+                .then(a.0.modifier.cmp(&b.0.modifier))
+        });
+        bill
+    }
+
+    /// Get the approximate amount of bytes used by the chunk, including block
+    /// data which may be shared with clones
+    pub fn memory_usage(&self) -> usize {
+        mem::size_of::<Self>() + mem::size_of_val(&*self.list)
+    }
+
+    /// Compress the chunk by run-length encoding its blocks, which is much
+    /// smaller for chunks with large uniform areas
+    pub fn into_compressed(self) -> CompressedChunk {
+        let mut runs: Vec<(Block, u32)> = Vec::new();
+        for block in self.list.iter() {
+            match runs.last_mut() {
+                Some((last, count)) if last == block => *count += 1,
+                _ => runs.push((*block, 1)),
+            }
+        }
+        runs.shrink_to_fit();
+        CompressedChunk {
+            runs,
+            origin: self.origin,
+            size: self.size,
+            anchor: self.anchor,
+            metadata: self.metadata,
+        }
+    }
+
+    /// Find all connected groups of blocks matching any of `ores`
+    ///
+    /// Blocks are connected if they share a face and are the same [`Block`]
+    pub fn find_ore_veins(&self, ores: &[Block]) -> Vec<Vein> {
+        let mut visited = vec![false; self.list.len()];
+        let mut veins = Vec::new();
+
+        for start in 0..self.list.len() {
+            let block = self.list[start];
+            if visited[start] || !ores.contains(&block) {
+                continue;
+            }
+
+            let mut positions = Vec::new();
+            let mut stack = vec![start];
+            visited[start] = true;
+            while let Some(index) = stack.pop() {
+                let coordinate = self.size.index_to_coordinate(index);
+                positions.push(coordinate + self.origin);
+                for offset in NEIGHBORS {
+                    let neighbor = coordinate + offset;
+                    if !self.size.contains(neighbor) {
+                        continue;
+                    }
+                    let neighbor = self.size.coordinate_to_index(neighbor);
+                    if !visited[neighbor] && self.list[neighbor] == block {
+                        visited[neighbor] = true;
+                        stack.push(neighbor);
+                    }
+                }
+            }
+
+            veins.push(Vein { block, positions });
+        }
+
+        veins
+    }
+}
+
+/// Identifies the binary format written by [`Chunk::write_to`]
+const MAGIC: &[u8; 8] = b"MCRSCHK2";
+
+fn read_i32(reader: &mut impl Read) -> io::Result<i32> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(i32::from_le_bytes(bytes))
+}
+
+fn read_coordinate(reader: &mut impl Read) -> io::Result<Coordinate> {
+    Ok(Coordinate::new(
+        read_i32(reader)?,
+        read_i32(reader)?,
+        read_i32(reader)?,
+    ))
+}
+
+/// Write a length-prefixed UTF-8 string
+pub(crate) fn write_string(writer: &mut impl Write, string: &str) -> io::Result<()> {
+    writer.write_all(&(string.len() as u32).to_le_bytes())?;
+    writer.write_all(string.as_bytes())
+}
+
+/// Read a string written with [`write_string`]
+pub(crate) fn read_string(reader: &mut impl Read) -> io::Result<String> {
+    let len = read_i32(reader)? as u32 as u64;
+    // Read incrementally, so a corrupted length cannot allocate everything
+    let mut bytes = Vec::new();
+    if reader.take(len).read_to_end(&mut bytes)? as u64 != len {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    String::from_utf8(bytes).map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+}
+
+/// Rotate a **relative** [`Coordinate`] within a [`Chunk`] of the given size
+/// a quarter turn clockwise around the vertical axis
+pub(crate) fn rotate_quarter(coordinate: Coordinate, size: Size) -> Coordinate {
+    Coordinate::new(size.z as i32 - 1 - coordinate.z, coordinate.y, coordinate.x)
+}
+
+/// Amount of items in a full stack
+pub const STACK_SIZE: usize = 64;
+/// Amount of items in a full shulker box (27 stacks)
+pub const SHULKER_SIZE: usize = STACK_SIZE * 27;
+
+/// Format an item count in shulker boxes, stacks and single items, eg.
+/// `3 shulkers + 2 stacks + 5` for `5317`
+pub fn format_item_count(count: usize) -> String {
+    let shulkers = count / SHULKER_SIZE;
+    let stacks = count % SHULKER_SIZE / STACK_SIZE;
+    let items = count % STACK_SIZE;
+
+    let mut parts = Vec::new();
+    if shulkers > 0 {
+        let plural = if shulkers == 1 { "" } else { "s" };
+        parts.push(format!("{} shulker{}", shulkers, plural));
+    }
+    if stacks > 0 {
+        let plural = if stacks == 1 { "" } else { "s" };
+        parts.push(format!("{} stack{}", stacks, plural));
+    }
+    if items > 0 || parts.is_empty() {
+        parts.push(items.to_string());
+    }
+    parts.join(" + ")
+}
+
+/// Estimated light levels for every block in a [`Chunk`]
+///
+/// Create with [`Chunk::estimate_sky_light`]
+#[derive(Clone)]
+pub struct LightMap {
+    levels: Vec<u8>,
+    origin: Coordinate,
+    size: Size,
+}
+
+impl LightMap {
+    /// Get the light level at the **relative** [`Coordinate`]
+    pub fn get(&self, coordinate: impl Into<Coordinate>) -> Option<u8> {
+        let coordinate = coordinate.into();
+        if !self.size.contains(coordinate) {
+            return None;
+        }
+        Some(self.levels[self.size.coordinate_to_index(coordinate)])
+    }
+
+    /// Get the origin [`Coordinate`]
+    pub fn origin(&self) -> Coordinate {
+        self.origin
+    }
+
+    /// Get the 3D size of the light map
+    pub fn size(&self) -> Size {
+        self.size
+    }
+}
+
+impl fmt::Debug for LightMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "<LightMap {}x{}x{}>",
+            self.size.x, self.size.y, self.size.z
+        )
+    }
+}
+
+/// A [`Chunk`] with its blocks stored run-length encoded
+///
+/// Create with [`Chunk::into_compressed`]
+#[derive(Clone)]
+pub struct CompressedChunk {
+    runs: Vec<(Block, u32)>,
+    origin: Coordinate,
+    size: Size,
+    anchor: Option<Coordinate>,
+    metadata: BTreeMap<String, String>,
+}
+
+impl CompressedChunk {
+    /// Get the origin [`Coordinate`]
+    pub fn origin(&self) -> Coordinate {
+        self.origin
+    }
+
+    /// Get the 3D size of the chunk
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// Get the approximate amount of bytes used by the compressed chunk
+    pub fn memory_usage(&self) -> usize {
+        mem::size_of::<Self>() + mem::size_of_val(self.runs.as_slice())
+    }
+
+    /// Decompress back into a [`Chunk`]
+    pub fn into_chunk(self) -> Chunk {
+        let list: Vec<Block> = self
+            .runs
+            .iter()
+            .flat_map(|(block, count)| std::iter::repeat_n(*block, *count as usize))
+            .collect();
+        Chunk {
+            list: list.into(),
+            origin: self.origin,
+            size: self.size,
+            anchor: self.anchor,
+            metadata: self.metadata,
+        }
+    }
+}
+
+impl fmt::Debug for CompressedChunk {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "<CompressedChunk {}x{}x{}, {} runs>",
+            self.size.x,
+            self.size.y,
+            self.size.z,
+            self.runs.len()
+        )
+    }
+}
+
+/// A [`Chunk`] with its blocks stored as `u16` indices into a [`BlockPalette`]
+///
+/// Uses a quarter of the memory of a [`Chunk`] for the block data, as long as
+/// there are at most 65536 distinct blocks
+#[derive(Clone)]
+pub struct SmallChunk {
+    indices: Vec<u16>,
+    palette: BlockPalette,
+    origin: Coordinate,
+    size: Size,
+    anchor: Option<Coordinate>,
+    metadata: BTreeMap<String, String>,
+}
+
+/// A list of distinct [`Block`]s, referenced by index
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BlockPalette {
+    blocks: Vec<Block>,
+}
+
+impl BlockPalette {
+    /// Create an empty palette
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the index of a [`Block`], adding it to the palette if it is not
+    /// already present
+    ///
+    /// Returns `None` if the palette is full
+    pub fn insert(&mut self, block: Block) -> Option<u16> {
+        if let Some(index) = self.index_of(block) {
+            return Some(index);
+        }
+        let index = u16::try_from(self.blocks.len()).ok()?;
+        self.blocks.push(block);
+        Some(index)
+    }
+
+    /// Get the index of a [`Block`] in the palette, if present
+    pub fn index_of(&self, block: Block) -> Option<u16> {
+        let index = self.blocks.iter().position(|other| *other == block)?;
+        Some(index as u16)
+    }
+
+    /// Get the [`Block`] at an index in the palette
+    pub fn get(&self, index: u16) -> Option<Block> {
+        self.blocks.get(index as usize).copied()
+    }
+
+    /// Get all [`Block`]s in the palette, in index order
+    pub fn blocks(&self) -> &[Block] {
+        &self.blocks
+    }
+
+    /// Get the amount of blocks in the palette
+    pub fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Returns `true` if the palette contains no blocks
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+}
+
+impl SmallChunk {
+    /// Convert a [`Chunk`] into its palette representation
+    ///
+    /// Returns `None` if the chunk has more than 65536 distinct blocks
+    pub fn from_chunk(chunk: &Chunk) -> Option<Self> {
+        let mut palette = BlockPalette::new();
+        let mut lookup = HashMap::new();
+        let mut indices = Vec::with_capacity(chunk.list.len());
+        for block in chunk.list.iter() {
+            let index = match lookup.get(block) {
+                Some(index) => *index,
+                None => {
+                    let index = palette.insert(*block)?;
+                    lookup.insert(*block, index);
+                    index
+                }
+            };
+            indices.push(index);
+        }
+        Some(Self {
+            indices,
+            palette,
+            origin: chunk.origin,
+            size: chunk.size,
+            anchor: chunk.anchor,
+            metadata: chunk.metadata.clone(),
+        })
+    }
+
+    /// Convert back into a [`Chunk`]
+    pub fn to_chunk(&self) -> Chunk {
+        let list: Vec<Block> = self
+            .indices
+            .iter()
+            .map(|index| {
+                self.palette
+                    .get(*index)
+                    .expect("palette should contain every index")
+            })
+            .collect();
+        Chunk {
+            list: list.into(),
+            origin: self.origin,
+            size: self.size,
+            anchor: self.anchor,
+            metadata: self.metadata.clone(),
+        }
+    }
+
+    /// Get the [`Block`] at the **relative** [`Coordinate`]
+    pub fn get(&self, coordinate: impl Into<Coordinate>) -> Option<Block> {
+        let coordinate = coordinate.into();
+        if !self.size.contains(coordinate) {
+            return None;
+        }
+        let index = self.indices[self.size.coordinate_to_index(coordinate)];
+        self.palette.get(index)
+    }
+
+    /// Get the [`BlockPalette`] used by the chunk
+    pub fn palette(&self) -> &BlockPalette {
+        &self.palette
+    }
+
+    /// Get the origin [`Coordinate`]
+    pub fn origin(&self) -> Coordinate {
+        self.origin
+    }
+
+    /// Get the 3D size of the chunk
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// Get the approximate amount of bytes used by the chunk
+    pub fn memory_usage(&self) -> usize {
+        mem::size_of::<Self>()
+            + mem::size_of_val(self.indices.as_slice())
+            + mem::size_of_val(self.palette.blocks())
+    }
+}
+
+impl From<&SmallChunk> for Chunk {
+    fn from(chunk: &SmallChunk) -> Self {
+        chunk.to_chunk()
+    }
+}
+
+impl fmt::Debug for SmallChunk {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "<SmallChunk {}x{}x{}, {} blocks in palette>",
+            self.size.x,
+            self.size.y,
+            self.size.z,
+            self.palette.len()
+        )
+    }
+}
+
+/// A [`Chunk`] with its blocks stored in an octree, where any cube of
+/// identical blocks is stored as a single node
+///
+/// Efficient for large captures with big uniform areas, and for checking
+/// whether a cuboid is entirely one [`Block`] (such as air) without visiting
+/// every block. See [`OctreeChunk::downsample`] for level-of-detail views
+#[derive(Clone)]
+pub struct OctreeChunk {
+    root: OctreeNode,
+    /// Side length of the root cube is `2^depth`
+    depth: u32,
+    origin: Coordinate,
+    size: Size,
+    anchor: Option<Coordinate>,
+    metadata: BTreeMap<String, String>,
+}
+
+#[derive(Clone)]
+enum OctreeNode {
+    /// A cube of identical blocks, or `None` if outside of the chunk
+    Leaf(Option<Block>),
+    Branch {
+        /// Indexed by `x + y*2 + z*4`, with each bit set for the upper half
+        children: Box<[OctreeNode; 8]>,
+        /// Most common block among the children
+        representative: Option<Block>,
+    },
+}
+
+impl OctreeChunk {
+    /// Convert a [`Chunk`] into its octree representation
+    pub fn from_chunk(chunk: &Chunk) -> Self {
+        let largest = chunk.size.x.max(chunk.size.y).max(chunk.size.z);
+        let depth = largest.next_power_of_two().trailing_zeros();
+        Self {
+            root: OctreeNode::build(chunk, Coordinate::new(0, 0, 0), 1 << depth),
+            depth,
+            origin: chunk.origin,
+            size: chunk.size,
+            anchor: chunk.anchor,
+            metadata: chunk.metadata.clone(),
+        }
+    }
+
+    /// Convert back into a [`Chunk`]
+    pub fn to_chunk(&self) -> Chunk {
+        let list: Vec<Block> = (0..self.size.volume())
+            .map(|index| {
+                self.get(self.size.index_to_coordinate(index))
+                    .expect("octree should contain every coordinate in size")
+            })
+            .collect();
+        Chunk {
+            list: list.into(),
+            origin: self.origin,
+            size: self.size,
+            anchor: self.anchor,
+            metadata: self.metadata.clone(),
+        }
+    }
+
+    /// Get the [`Block`] at the **relative** [`Coordinate`]
+    pub fn get(&self, coordinate: impl Into<Coordinate>) -> Option<Block> {
+        let coordinate = coordinate.into();
+        if !self.size.contains(coordinate) {
+            return None;
+        }
+        let mut node = &self.root;
+        let mut half = (1 << self.depth) / 2;
+        loop {
+            match node {
+                OctreeNode::Leaf(block) => return *block,
+                OctreeNode::Branch { children, .. } => {
+                    node = &children[child_index(coordinate, half)];
+                    half /= 2;
+                }
+            }
+        }
+    }
+
+    /// Get the single [`Block`] filling the cuboid specified by **relative**
+    /// [`Coordinate`]s `a` and `b` (in any order), or `None` if it contains
+    /// different blocks
+    ///
+    /// Parts of the cuboid outside of the chunk are ignored. Returns `None` if
+    /// the cuboid is entirely outside of the chunk
+    pub fn uniform_block(
+        &self,
+        a: impl Into<Coordinate>,
+        b: impl Into<Coordinate>,
+    ) -> Option<Block> {
+        let a = a.into();
+        let b = b.into();
+        let max = Coordinate::new(
+            self.size.x as i32 - 1,
+            self.size.y as i32 - 1,
+            self.size.z as i32 - 1,
+        );
+        let query = (a.min(b).max(Coordinate::new(0, 0, 0)), a.max(b).min(max));
+        let mut found = None;
+        let side = 1 << self.depth;
+        if self
+            .root
+            .is_uniform(Coordinate::new(0, 0, 0), side, query, &mut found)
+        {
+            found
+        } else {
+            None
+        }
+    }
+
+    /// Returns `true` if every block in the cuboid specified by **relative**
+    /// [`Coordinate`]s `a` and `b` (in any order) is air
+    ///
+    /// See [`OctreeChunk::uniform_block`]
+    pub fn is_all_air(&self, a: impl Into<Coordinate>, b: impl Into<Coordinate>) -> bool {
+        self.uniform_block(a, b) == Some(Block::AIR)
+    }
+
+    /// Create a lower resolution copy of the chunk, where each cube of
+    /// `2^level` blocks along each side becomes a single block
+    ///
+    /// Uniform cubes keep their block. Other cubes use the most common block
+    /// among their eight sub-cubes, applied recursively. The copy keeps the
+    /// same origin, but not the anchor or metadata
+    pub fn downsample(&self, level: u32) -> Chunk {
+        let level = level.min(self.depth);
+        let scale = 1u32 << level;
+        let size = Size {
+            x: self.size.x.div_ceil(scale),
+            y: self.size.y.div_ceil(scale),
+            z: self.size.z.div_ceil(scale),
+        };
+        let list: Vec<Block> = (0..size.volume())
+            .map(|index| {
+                let cell = size.index_to_coordinate(index);
+                let coordinate = Coordinate::new(
+                    cell.x * scale as i32,
+                    cell.y * scale as i32,
+                    cell.z * scale as i32,
+                );
+                let mut node = &self.root;
+                let mut half = (1 << self.depth) / 2;
+                for _ in 0..self.depth - level {
+                    match node {
+                        OctreeNode::Leaf(_) => break,
+                        OctreeNode::Branch { children, .. } => {
+                            node = &children[child_index(coordinate, half)];
+                            half /= 2;
+                        }
+                    }
+                }
+                node.representative().unwrap_or(Block::AIR)
+            })
+            .collect();
+        Chunk {
+            list: list.into(),
+            origin: self.origin,
+            size,
+            anchor: None,
+            metadata: BTreeMap::new(),
+        }
+    }
+
+    /// Get the origin [`Coordinate`]
+    pub fn origin(&self) -> Coordinate {
+        self.origin
+    }
+
+    /// Get the 3D size of the chunk
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// Get the amount of nodes in the octree
+    pub fn node_count(&self) -> usize {
+        self.root.count()
+    }
+
+    /// Get the approximate amount of bytes used by the chunk
+    pub fn memory_usage(&self) -> usize {
+        mem::size_of::<Self>() + (self.node_count() - 1) * mem::size_of::<OctreeNode>()
+    }
+}
+
+impl OctreeNode {
+    /// Build the node for the cube with its minimum corner at the **relative**
+    /// [`Coordinate`] `min`
+    fn build(chunk: &Chunk, min: Coordinate, side: i32) -> Self {
+        let size = chunk.size;
+        let outside = min.x >= size.x as i32 || min.y >= size.y as i32 || min.z >= size.z as i32;
+        if outside {
+            return Self::Leaf(None);
+        }
+        if side == 1 {
+            return Self::Leaf(chunk.get(min));
+        }
+
+        let half = side / 2;
+        let children: [Self; 8] = std::array::from_fn(|index| {
+            let offset =
+                [index & 1, (index >> 1) & 1, (index >> 2) & 1].map(|bit| bit as i32 * half);
+            Self::build(chunk, min + offset, half)
+        });
+        if let Self::Leaf(first) = children[0] {
+            if children
+                .iter()
+                .all(|child| matches!(child, Self::Leaf(block) if *block == first))
+            {
+                return Self::Leaf(first);
+            }
+        }
+
+        let mut counts: Vec<(Block, usize)> = Vec::new();
+        for block in children.iter().filter_map(Self::representative) {
+            match counts.iter_mut().find(|(other, _)| *other == block) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((block, 1)),
+            }
+        }
+        let representative = counts
+            .iter()
+            .rev()
+            .max_by_key(|(_, count)| *count)
+            .map(|(block, _)| *block);
+        Self::Branch {
+            children: Box::new(children),
+            representative,
+        }
+    }
+
+    fn representative(&self) -> Option<Block> {
+        match self {
+            Self::Leaf(block) => *block,
+            Self::Branch { representative, .. } => *representative,
+        }
+    }
+
+    fn count(&self) -> usize {
+        match self {
+            Self::Leaf(_) => 1,
+            Self::Branch { children, .. } => 1 + children.iter().map(Self::count).sum::<usize>(),
+        }
+    }
+
+    /// Returns `false` if the overlap of this node and the `query` cuboid
+    /// contains a block other than `found`, setting `found` to the first block
+    /// seen
+    fn is_uniform(
+        &self,
+        min: Coordinate,
+        side: i32,
+        query: (Coordinate, Coordinate),
+        found: &mut Option<Block>,
+    ) -> bool {
+        let (query_min, query_max) = query;
+        let overlaps = min.x <= query_max.x
+            && min.y <= query_max.y
+            && min.z <= query_max.z
+            && min.x + side > query_min.x
+            && min.y + side > query_min.y
+            && min.z + side > query_min.z;
+        if !overlaps {
+            return true;
+        }
+        match self {
+            Self::Leaf(None) => true,
+            Self::Leaf(Some(block)) => match found {
+                Some(found) => found == block,
+                None => {
+                    *found = Some(*block);
+                    true
+                }
+            },
+            Self::Branch { children, .. } => {
+                let half = side / 2;
+                children.iter().enumerate().all(|(index, child)| {
+                    let offset = [index & 1, (index >> 1) & 1, (index >> 2) & 1]
+                        .map(|bit| bit as i32 * half);
+                    child.is_uniform(min + offset, half, query, found)
+                })
+            }
+        }
+    }
+}
+
+impl From<&OctreeChunk> for Chunk {
+    fn from(chunk: &OctreeChunk) -> Self {
+        chunk.to_chunk()
+    }
+}
+
+impl fmt::Debug for OctreeChunk {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "<OctreeChunk {}x{}x{}, {} nodes>",
+            self.size.x,
+            self.size.y,
+            self.size.z,
+            self.node_count()
+        )
+    }
+}
+
+/// Get the index of the child of an octree node containing the **relative**
+/// [`Coordinate`], where `half` is half the side length of the node
+fn child_index(coordinate: Coordinate, half: i32) -> usize {
+    let bit = |value: i32| usize::from(value & half != 0);
+    bit(coordinate.x) | bit(coordinate.y) << 1 | bit(coordinate.z) << 2
+}
+
+/// A connected group of identical ore blocks found in a [`Chunk`]
+#[derive(Clone, Debug)]
+pub struct Vein {
+    block: Block,
+    positions: Vec<Coordinate>,
+}
+
+impl Vein {
+    /// Get the [`Block`] the vein is made of
+    pub fn block(&self) -> Block {
+        self.block
+    }
+
+    /// Get the **absolute** [`Coordinate`]s of every block in the vein
+    pub fn positions(&self) -> &[Coordinate] {
+        &self.positions
+    }
+
+    /// Get the amount of blocks in the vein
+    pub fn size(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Get the amount of blocks in the vein at each `y`-value, lowest first
+    pub fn y_distribution(&self) -> Vec<(i32, usize)> {
+        let mut distribution: Vec<(i32, usize)> = Vec::new();
+        for position in &self.positions {
+            match distribution.iter_mut().find(|(y, _)| *y == position.y) {
+                Some((_, count)) => *count += 1,
+                None => distribution.push((position.y, 1)),
+            }
+        }
+        distribution.sort_unstable();
+        distribution
+    }
 }
 
 impl Size {
+    /// Get the amount of blocks in a [`Chunk`] of this size
+    pub fn volume(&self) -> usize {
+        self.x as usize * self.y as usize * self.z as usize
+    }
+
+    /// Get the amount of blocks in a [`Chunk`] of this size, or `None` if it
+    /// overflows `usize`
+    pub fn checked_volume(&self) -> Option<usize> {
+        (self.x as usize)
+            .checked_mul(self.y as usize)?
+            .checked_mul(self.z as usize)
+    }
+
     /// Convert a [`Chunk`] index to a **relative** [`Coordinate`]
     pub fn index_to_coordinate(&self, index: usize) -> Coordinate {
         let z = (index % self.z as usize) as i32;
@@ -97,6 +1275,48 @@ impl Size {
     }
 }
 
+/// A horizontal slice of a [`Chunk`], displayed with one row per `z`-value
+/// (north at the top) and one character per block
+///
+/// Air is shown as ` `, liquids as `~`, vegetation as `*`, other transparent
+/// blocks as `.` and every other block as `#`. A `y`-value outside of the
+/// chunk displays nothing
+///
+/// Create with [`Chunk::cross_section`]
+#[derive(Clone, Copy, Debug)]
+pub struct CrossSection<'a> {
+    chunk: &'a Chunk,
+    y: i32,
+}
+
+impl fmt::Display for CrossSection<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let size = self.chunk.size;
+        if !(0..size.y as i32).contains(&self.y) {
+            return Ok(());
+        }
+        for z in 0..size.z as i32 {
+            for x in 0..size.x as i32 {
+                let block = self.chunk.list[size.coordinate_to_index([x, self.y, z])];
+                let char = if block == Block::AIR {
+                    ' '
+                } else if block.is_liquid() {
+                    '~'
+                } else if block.is_vegetation() {
+                    '*'
+                } else if block.is_transparent() {
+                    '.'
+                } else {
+                    '#'
+                };
+                write!(f, "{}", char)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
 impl fmt::Debug for Chunk {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "<Chunk {}x{}x{}>", self.size.x, self.size.y, self.size.z)
@@ -164,3 +1384,199 @@ impl<'a> IterItem<'a> {
         self.position_relative() + self.chunk.origin
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(size: [i32; 3]) -> Chunk {
+        let [x, y, z] = size;
+        let list = (0..x * y * z).map(|i| Block::new(i % 7, i % 3)).collect();
+        Chunk::new([5, 60, -3], [5 + x - 1, 60 + y - 1, -3 + z - 1], list)
+    }
+
+    fn blocks(chunk: &Chunk) -> Vec<Block> {
+        chunk.list.to_vec()
+    }
+
+    #[test]
+    fn binary_round_trip() {
+        let mut original = chunk([3, 2, 5]).with_anchor([1, -1, 2]);
+        original.set_metadata("name", "house");
+        original.set_metadata("author", "");
+        let mut bytes = Vec::new();
+        original.write_to(&mut bytes).unwrap();
+        assert_eq!(&bytes[..8], MAGIC);
+
+        let read = Chunk::read_from(bytes.as_slice()).unwrap();
+        assert_eq!(read.origin(), original.origin());
+        assert_eq!(read.anchor(), Some(Coordinate::new(1, -1, 2)));
+        assert_eq!(read.metadata("name"), Some("house"));
+        assert_eq!(read.metadata("author"), Some(""));
+        assert_eq!(blocks(&read), blocks(&original));
+    }
+
+    #[test]
+    fn rejects_unknown_magic() {
+        let error = Chunk::read_from(&b"MCRSCHK9"[..]).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn rejects_truncated_data() {
+        let mut bytes = Vec::new();
+        chunk([2, 2, 2]).write_to(&mut bytes).unwrap();
+        bytes.pop();
+        let error = Chunk::read_from(bytes.as_slice()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    /// Create a chunk at the origin, with each block chosen by its **relative**
+    /// coordinate
+    fn filled(size: [i32; 3], block: impl Fn(Coordinate) -> Block) -> Chunk {
+        let [x, y, z] = size;
+        let size = Coordinate::new(0, 0, 0).size_between(Coordinate::new(x - 1, y - 1, z - 1));
+        let list = (0..size.volume())
+            .map(|index| block(size.index_to_coordinate(index)))
+            .collect();
+        Chunk::new([0, 0, 0], [x - 1, y - 1, z - 1], list)
+    }
+
+    #[test]
+    fn child_index_bits() {
+        assert_eq!(child_index(Coordinate::new(0, 0, 0), 4), 0);
+        assert_eq!(child_index(Coordinate::new(4, 0, 0), 4), 1);
+        assert_eq!(child_index(Coordinate::new(0, 4, 0), 4), 2);
+        assert_eq!(child_index(Coordinate::new(0, 0, 4), 4), 4);
+        assert_eq!(child_index(Coordinate::new(7, 7, 7), 4), 7);
+        // Only the bit for the current level matters
+        assert_eq!(child_index(Coordinate::new(3, 11, 8), 4), 0);
+        assert_eq!(child_index(Coordinate::new(3, 11, 8), 2), 3);
+        assert_eq!(child_index(Coordinate::new(3, 11, 8), 1), 3);
+    }
+
+    #[test]
+    fn octree_round_trip() {
+        for size in [[1, 1, 1], [3, 5, 7], [16, 1, 1], [8, 8, 8], [2, 9, 3]] {
+            let original = filled(size, |c| Block::new((c.x * 3 + c.y) % 4, c.z % 2));
+            let octree = OctreeChunk::from_chunk(&original);
+            let round_trip = octree.to_chunk();
+            assert_eq!(blocks(&round_trip), blocks(&original), "{size:?}");
+            assert!(octree.get([size[0], 0, 0]).is_none());
+            assert!(octree.get([-1, 0, 0]).is_none());
+        }
+    }
+
+    #[test]
+    fn octree_keeps_position_and_metadata() {
+        let mut original = chunk([3, 2, 5]).with_anchor([1, 0, 1]);
+        original.set_metadata("name", "wall");
+        let round_trip = OctreeChunk::from_chunk(&original).to_chunk();
+        assert_eq!(round_trip.origin(), original.origin());
+        assert_eq!(round_trip.anchor(), original.anchor());
+        assert_eq!(round_trip.metadata("name"), Some("wall"));
+    }
+
+    #[test]
+    fn uniform_chunk_is_one_node() {
+        let octree = OctreeChunk::from_chunk(&filled([8, 8, 8], |_| Block::STONE));
+        assert_eq!(octree.node_count(), 1);
+        assert_eq!(
+            octree.uniform_block([0, 0, 0], [7, 7, 7]),
+            Some(Block::STONE)
+        );
+
+        let octree = OctreeChunk::from_chunk(&filled([1, 1, 1], |_| Block::GLASS));
+        assert_eq!(octree.node_count(), 1);
+        assert_eq!(
+            octree.uniform_block([0, 0, 0], [0, 0, 0]),
+            Some(Block::GLASS)
+        );
+    }
+
+    #[test]
+    fn uniform_block() {
+        // Air, with a single stone block at (4, 1, 2)
+        let octree = OctreeChunk::from_chunk(&filled([6, 3, 5], |c| {
+            if c == Coordinate::new(4, 1, 2) {
+                Block::STONE
+            } else {
+                Block::AIR
+            }
+        }));
+        assert_eq!(octree.uniform_block([0, 0, 0], [5, 2, 4]), None);
+        assert_eq!(
+            octree.uniform_block([4, 1, 2], [4, 1, 2]),
+            Some(Block::STONE)
+        );
+        assert_eq!(octree.uniform_block([3, 2, 4], [0, 0, 0]), Some(Block::AIR));
+        assert_eq!(octree.uniform_block([5, 0, 0], [5, 2, 4]), Some(Block::AIR));
+        assert!(octree.is_all_air([0, 0, 3], [5, 2, 4]));
+        assert!(!octree.is_all_air([3, 0, 0], [5, 2, 4]));
+
+        // Parts outside of the chunk are ignored
+        assert_eq!(
+            octree.uniform_block([-10, -10, -10], [3, 2, 1]),
+            Some(Block::AIR)
+        );
+        assert_eq!(
+            octree.uniform_block([5, 0, 0], [100, 100, 100]),
+            Some(Block::AIR)
+        );
+        assert_eq!(octree.uniform_block([6, 0, 0], [100, 100, 100]), None);
+        assert_eq!(octree.uniform_block([-5, -5, -5], [-1, -1, -1]), None);
+    }
+
+    #[test]
+    fn downsample() {
+        // Each 2x2x2 cube is stone, except one which is mostly glass
+        let original = filled([4, 4, 4], |c| {
+            let glass_cube = c.x >= 2 && c.y < 2 && c.z < 2;
+            if glass_cube && c != Coordinate::new(3, 1, 1) {
+                Block::GLASS
+            } else {
+                Block::STONE
+            }
+        });
+        let octree = OctreeChunk::from_chunk(&original);
+
+        assert_eq!(blocks(&octree.downsample(0)), blocks(&original));
+
+        let half = octree.downsample(1);
+        assert_eq!(half.size().x, 2);
+        assert_eq!(half.origin(), original.origin());
+        assert_eq!(half.get([1, 0, 0]), Some(Block::GLASS));
+        assert_eq!(half.get([0, 0, 0]), Some(Block::STONE));
+        assert_eq!(half.get([1, 1, 1]), Some(Block::STONE));
+
+        let single = octree.downsample(2);
+        assert_eq!(blocks(&single), [Block::STONE]);
+        // Levels beyond the depth of the octree give a single block
+        assert_eq!(blocks(&octree.downsample(10)), [Block::STONE]);
+    }
+
+    #[test]
+    fn downsample_rounds_size_up() {
+        let octree = OctreeChunk::from_chunk(&filled([3, 1, 5], |_| Block::DIRT));
+        let half = octree.downsample(1);
+        let size = half.size();
+        assert_eq!((size.x, size.y, size.z), (2, 1, 3));
+        assert!(blocks(&half).iter().all(|block| *block == Block::DIRT));
+        assert!(half.anchor().is_none());
+    }
+
+    #[test]
+    fn rejects_invalid_size() {
+        for size in [[0, 1, 1], [1, 1, 0], [-1, 1, 1], [i32::MIN, 1, 1]] {
+            let mut bytes = MAGIC.to_vec();
+            for value in [0, 0, 0].into_iter().chain(size) {
+                bytes.extend_from_slice(&i32::to_le_bytes(value));
+            }
+            // No anchor or metadata
+            bytes.push(0);
+            bytes.extend_from_slice(&i32::to_le_bytes(0));
+            let error = Chunk::read_from(bytes.as_slice()).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData, "{size:?}");
+        }
+    }
+}
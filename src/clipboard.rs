@@ -0,0 +1,189 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+use crate::{
+    chunk::{read_string, write_string},
+    Block, Chunk, Connection, Coordinate,
+};
+
+/// Named slots of copied [`Chunk`]s, each positioned by its anchor point when
+/// pasting (see [`Chunk::anchor`])
+///
+/// ```no_run
+/// # use mcrs::{Clipboard, Connection, Coordinate};
+/// let mut mc = Connection::new().unwrap();
+/// let mut clipboard = Clipboard::new();
+/// let door = Coordinate::new(12, 64, 5);
+/// clipboard.copy(&mut mc, "house", [10, 64, 0], [20, 72, 10], door).unwrap();
+/// clipboard.rotate("house", 1);
+/// clipboard.paste(&mut mc, "house", [40, 64, 0]).unwrap();
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Clipboard {
+    slots: HashMap<String, Chunk>,
+}
+
+/// Identifies the file format written by [`Clipboard::save`]
+const MAGIC: &[u8; 8] = b"MCRSCLP2";
+
+impl Clipboard {
+    /// Create an empty clipboard
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Capture the cuboid specified by [`Coordinate`]s `a` and `b` (in any
+    /// order) into a slot, replacing its contents
+    ///
+    /// `anchor` is an **absolute** [`Coordinate`], which is placed at the
+    /// target position when pasting
+    pub fn copy(
+        &mut self,
+        mc: &mut Connection,
+        name: impl Into<String>,
+        a: impl Into<Coordinate>,
+        b: impl Into<Coordinate>,
+        anchor: impl Into<Coordinate>,
+    ) -> io::Result<()> {
+        let chunk = mc.get_blocks(a, b)?;
+        let anchor = anchor.into() - chunk.origin();
+        self.insert(name, chunk.with_anchor(anchor));
+        Ok(())
+    }
+
+    /// Like [`Clipboard::copy`], then replace the cuboid with air
+    pub fn cut(
+        &mut self,
+        mc: &mut Connection,
+        name: impl Into<String>,
+        a: impl Into<Coordinate>,
+        b: impl Into<Coordinate>,
+        anchor: impl Into<Coordinate>,
+    ) -> io::Result<()> {
+        let a = a.into();
+        let b = b.into();
+        self.copy(mc, name, a, b, anchor)?;
+        mc.set_blocks(a, b, Block::AIR)
+    }
+
+    /// Place the contents of a slot with its anchor at the **absolute**
+    /// `position`
+    ///
+    /// Fails with [`io::ErrorKind::NotFound`] if the slot is empty
+    pub fn paste(
+        &self,
+        mc: &mut Connection,
+        name: &str,
+        position: impl Into<Coordinate>,
+    ) -> io::Result<()> {
+        let chunk = self
+            .slots
+            .get(name)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "clipboard slot is empty"))?;
+        mc.paste(chunk, position)
+    }
+
+    /// Rotate the contents of a slot clockwise (seen from above) by
+    /// `quarter_turns` multiples of 90 degrees, along with its anchor
+    ///
+    /// Returns `false` if the slot is empty. See [`Chunk::rotate`]
+    pub fn rotate(&mut self, name: &str, quarter_turns: i32) -> bool {
+        let Some(chunk) = self.slots.get_mut(name) else {
+            return false;
+        };
+        *chunk = chunk.rotate(quarter_turns);
+        true
+    }
+
+    /// Put a [`Chunk`] into a slot, replacing its contents
+    ///
+    /// The chunk is positioned by its anchor when pasting, if set
+    pub fn insert(&mut self, name: impl Into<String>, chunk: Chunk) {
+        self.slots.insert(name.into(), chunk);
+    }
+
+    /// Get the [`Chunk`] in a slot, if any
+    pub fn get(&self, name: &str) -> Option<&Chunk> {
+        self.slots.get(name)
+    }
+
+    /// Empty a slot, returning its [`Chunk`] if any
+    pub fn remove(&mut self, name: &str) -> Option<Chunk> {
+        self.slots.remove(name)
+    }
+
+    /// Create an iterator over the names of all filled slots
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.slots.keys().map(String::as_str)
+    }
+
+    /// Write every slot to a file, readable with [`Clipboard::load`]
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(MAGIC)?;
+        writer.write_all(&(self.slots.len() as u32).to_le_bytes())?;
+        for (name, chunk) in &self.slots {
+            write_string(&mut writer, name)?;
+            chunk.write_to(&mut writer)?;
+        }
+        writer.flush()
+    }
+
+    /// Read a clipboard written with [`Clipboard::save`]
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let mut reader = BufReader::new(File::open(path)?);
+        let mut magic = [0; MAGIC.len()];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "not a clipboard file",
+            ));
+        }
+
+        let mut clipboard = Self::new();
+        for _ in 0..read_u32(&mut reader)? {
+            let name = read_string(&mut reader)?;
+            clipboard.insert(name, Chunk::read_from(&mut reader)?);
+        }
+        Ok(clipboard)
+    }
+}
+
+fn read_u32(reader: &mut impl Read) -> io::Result<u32> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process};
+
+    use super::*;
+
+    #[test]
+    fn save_load_round_trip() {
+        let mut clipboard = Clipboard::new();
+        let chunk = Chunk::new(
+            [10, 64, 0],
+            [10, 64, 1],
+            vec![Block::COBBLESTONE, Block::AIR],
+        );
+        clipboard.insert("house", chunk.with_anchor([0, 0, 1]));
+        let path = env::temp_dir().join(format!("mcrs-clipboard-{}", process::id()));
+        clipboard.save(&path).unwrap();
+        let loaded = Clipboard::load(&path);
+        fs::remove_file(&path).unwrap();
+
+        let chunk = loaded.unwrap().remove("house").unwrap();
+        assert_eq!(chunk.origin(), Coordinate::new(10, 64, 0));
+        assert_eq!(chunk.anchor(), Some(Coordinate::new(0, 0, 1)));
+        assert_eq!(chunk.get([0, 0, 0]), Some(Block::COBBLESTONE));
+        assert_eq!(chunk.get([0, 0, 1]), Some(Block::AIR));
+    }
+}
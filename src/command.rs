@@ -1,5 +1,15 @@
 use crate::{Block, Coordinate};
 
+/// Line ending sent after each command
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LineEnding {
+    /// `\n`, as expected by most servers (default)
+    #[default]
+    Lf,
+    /// `\r\n`, for server forks which expect Windows line endings
+    CrLf,
+}
+
 #[derive(Debug)]
 pub struct Command {
     command: String,
@@ -44,8 +54,16 @@ impl Command {
         self.arg(block.id).arg(block.modifier)
     }
 
-    pub fn build(self) -> String {
-        self.command + ")\n"
+    pub fn name(&self) -> &str {
+        let end = self.command.find('(').expect("command should contain name");
+        &self.command[..end]
+    }
+
+    pub fn build(self, line_ending: LineEnding) -> String {
+        match line_ending {
+            LineEnding::Lf => self.command + ")\n",
+            LineEnding::CrLf => self.command + ")\r\n",
+        }
     }
 }
 
@@ -71,7 +89,7 @@ impl Arg for i32 {
     }
 }
 
-fn is_valid_command_name(name: &str) -> bool {
+pub(crate) fn is_valid_command_name(name: &str) -> bool {
     if name.is_empty() {
         return false;
     }
@@ -0,0 +1,32 @@
+use crate::Block;
+
+/// Execution mode of a command block, corresponding to its block type
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CommandBlockMode {
+    /// Runs once each time it is activated
+    Impulse,
+    /// Runs when the command block pointing into it runs
+    Chain,
+    /// Runs every game tick while activated
+    Repeat,
+}
+
+impl CommandBlockMode {
+    /// Get the legacy [`Block`] for the mode, without any state
+    pub(crate) fn block(&self) -> Block {
+        match self {
+            Self::Impulse => Block::COMMAND_BLOCK,
+            Self::Chain => Block::CHAIN_COMMAND_BLOCK,
+            Self::Repeat => Block::REPEATING_COMMAND_BLOCK,
+        }
+    }
+
+    /// Get the Minecraft block name for the mode
+    pub(crate) fn block_name(&self) -> &'static str {
+        match self {
+            Self::Impulse => "minecraft:command_block",
+            Self::Chain => "minecraft:chain_command_block",
+            Self::Repeat => "minecraft:repeating_command_block",
+        }
+    }
+}
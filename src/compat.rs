@@ -0,0 +1,4 @@
+/// Mirror of the [mcpp](https://github.com/rozukke/mcpp) C++ API, for porting
+/// code from mcpp
+#[cfg(feature = "mcpp")]
+pub mod mcpp;
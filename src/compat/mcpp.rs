@@ -0,0 +1,126 @@
+//! Method names, parameter order and types follow mcpp, so code can be
+//! ported nearly line by line. Methods which throw in mcpp return an
+//! [`std::io::Result`] instead
+//!
+//! ```no_run
+//! # use mcrs::compat::mcpp::{BlockType, Blocks, Coordinate, MinecraftConnection};
+//! let mut mc = MinecraftConnection::new("localhost", 4711).unwrap();
+//! mc.postToChat("Hello from Rust!").unwrap();
+//! let pos = mc.getPlayerPosition().unwrap();
+//! let block: BlockType = Blocks::STONE;
+//! mc.setBlock(pos + Coordinate::new(0, -1, 0), block).unwrap();
+//! ```
+
+#![allow(non_snake_case)]
+
+use std::{
+    io,
+    ops::{Deref, DerefMut},
+};
+
+use crate::{Block, Chunk, Connection, HeightMap};
+
+pub use crate::Coordinate;
+
+/// `mcpp::BlockType`
+pub type BlockType = Block;
+
+/// `mcpp::Blocks`, the block constants
+pub type Blocks = Block;
+
+/// `mcpp::MinecraftConnection`
+///
+/// Dereferences to [`Connection`], so the native methods are also available
+#[derive(Debug)]
+pub struct MinecraftConnection {
+    connection: Connection,
+}
+
+impl MinecraftConnection {
+    /// Connect to the server at `address` and `port`, such as `"localhost"`
+    /// and `4711` (the mcpp defaults)
+    pub fn new(address: &str, port: u16) -> io::Result<Self> {
+        Ok(Self {
+            connection: Connection::with_address::<(&str, u16)>((address, port))?,
+        })
+    }
+
+    /// `postToChat(message)`
+    pub fn postToChat(&mut self, message: &str) -> io::Result<()> {
+        self.connection.post_to_chat(message)
+    }
+
+    /// `doCommand(command)`
+    pub fn doCommand(&mut self, command: &str) -> io::Result<()> {
+        self.connection.do_command(command)
+    }
+
+    /// `setPlayerPosition(pos)`
+    pub fn setPlayerPosition(&mut self, pos: Coordinate) -> io::Result<()> {
+        self.connection.set_player_position(pos)
+    }
+
+    /// `getPlayerPosition()`
+    pub fn getPlayerPosition(&mut self) -> io::Result<Coordinate> {
+        self.connection.get_player_position()
+    }
+
+    /// `setPlayerTilePosition(tile)`
+    pub fn setPlayerTilePosition(&mut self, tile: Coordinate) -> io::Result<()> {
+        self.connection.set_player_tile_position(tile)
+    }
+
+    /// `getPlayerTilePosition()`
+    pub fn getPlayerTilePosition(&mut self) -> io::Result<Coordinate> {
+        self.connection.get_player_tile_position()
+    }
+
+    /// `setBlock(loc, blockType)`
+    pub fn setBlock(&mut self, loc: Coordinate, blockType: BlockType) -> io::Result<()> {
+        self.connection.set_block(loc, blockType)
+    }
+
+    /// `setBlocks(loc1, loc2, blockType)`
+    pub fn setBlocks(
+        &mut self,
+        loc1: Coordinate,
+        loc2: Coordinate,
+        blockType: BlockType,
+    ) -> io::Result<()> {
+        self.connection.set_blocks(loc1, loc2, blockType)
+    }
+
+    /// `getBlock(loc)`
+    pub fn getBlock(&mut self, loc: Coordinate) -> io::Result<BlockType> {
+        self.connection.get_block(loc)
+    }
+
+    /// `getBlocks(loc1, loc2)`
+    pub fn getBlocks(&mut self, loc1: Coordinate, loc2: Coordinate) -> io::Result<Chunk> {
+        self.connection.get_blocks(loc1, loc2)
+    }
+
+    /// `getHeight(x, z)`
+    pub fn getHeight(&mut self, x: i32, z: i32) -> io::Result<i32> {
+        self.connection.get_height(x, z)
+    }
+
+    /// `getHeights(loc1, loc2)`
+    pub fn getHeights(&mut self, loc1: Coordinate, loc2: Coordinate) -> io::Result<HeightMap> {
+        self.connection.get_heights(loc1, loc2)
+    }
+}
+
+impl Deref for MinecraftConnection {
+    type Target = Connection;
+
+    fn deref(&self) -> &Self::Target {
+        &self.connection
+    }
+}
+
+impl DerefMut for MinecraftConnection {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.connection
+    }
+}
@@ -1,18 +1,93 @@
 use std::{
+    collections::HashMap,
     io::{self, BufRead, BufReader, Write},
     net::{TcpStream, ToSocketAddrs},
+    ops::ControlFlow,
+    path::Path,
+    thread,
+    time::{Duration, Instant},
 };
 
 use crate::{
-    command::Command, height_map::HeightMap, response::Response, Block, Chunk, Coordinate,
+    block_state::{self, Facing, StairState},
+    cache::ReadCache,
+    command::{is_valid_command_name, Command, LineEnding},
+    command_block::CommandBlockMode,
+    failover::Failover,
+    height_map::HeightMap,
+    journal::Journal,
+    metrics::Metrics,
+    preview::Preview,
+    response::{ParseMode, Response},
+    transport::Transport,
+    undo::{Entry, UndoHistory},
+    Axis, Batch, Block, BridgeStyle, Chunk, Coordinate, EditLog, Effect, FillHeight, Flow,
+    NoiseParams, OutOfWorld, Polygon, Profile, Timing, WorldLimits,
 };
 
 type Result<T> = io::Result<T>;
 
+/// Most requests sent before reading their responses, so the server is never
+/// left blocked on a full socket while the client is still writing
+const PIPELINE_WINDOW: usize = 1024;
+
+/// Side length of the cubic tiles read at a time when clearing blocks, such as
+/// by [`Connection::drain_liquids`]
+const CLEAR_TILE: i32 = 32;
+
 /// Connection for Minecraft server
 #[derive(Debug)]
 pub struct Connection {
-    stream: TcpStream,
+    stream: BufReader<Box<dyn Transport>>,
+    last_operation: Option<Timing>,
+    edit_log: Option<EditLog>,
+    undo_history: Option<UndoHistory>,
+    journal: Option<Journal>,
+    parse_mode: ParseMode,
+    profile: Profile,
+    world_limits: Option<WorldLimits>,
+    metrics: Metrics,
+    line_ending: LineEnding,
+    flow: Flow,
+    /// Writes sent since the server last answered a query
+    unacknowledged_writes: u32,
+    failover: Option<Failover>,
+    cache: Option<ReadCache>,
+    /// Commands serialized but not yet written
+    write_buffer: Vec<u8>,
+    /// Amount of buffered bytes which triggers a write
+    write_capacity: usize,
+}
+
+/// Configuration for a [`Connection`], applied when connecting
+///
+/// ```no_run
+/// # use std::time::Duration;
+/// # use mcrs::{Connection, Flow};
+/// let mut mc = Connection::builder()
+///     .address("192.168.1.20:4711")
+///     .connect_timeout(Duration::from_secs(5))
+///     .read_timeout(Duration::from_secs(10))
+///     .retries(3, Duration::from_secs(1))
+///     .flow(Flow::Paced(1000))
+///     .connect()
+///     .unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub struct ConnectionBuilder {
+    address: String,
+    connect_timeout: Option<Duration>,
+    read_timeout: Option<Duration>,
+    write_timeout: Option<Duration>,
+    retries: u32,
+    retry_delay: Duration,
+    parse_mode: ParseMode,
+    profile: Profile,
+    line_ending: LineEnding,
+    flow: Flow,
+    world_limits: Option<WorldLimits>,
+    nodelay: bool,
+    write_buffer: usize,
 }
 
 impl Connection {
@@ -29,21 +104,647 @@ impl Connection {
     /// Create a new connection with a specified server address
     pub fn with_address<A>(addr: impl ToSocketAddrs) -> Result<Self> {
         let stream = TcpStream::connect(addr)?;
-        Ok(Self { stream })
+        Ok(Self::from_transport(stream))
+    }
+
+    /// Create a new connection with a specified server address, failing with
+    /// [`io::ErrorKind::TimedOut`] if connecting takes longer than `timeout`
+    ///
+    /// If the address resolves to multiple addresses, each is tried in turn
+    /// with the full timeout
+    pub fn with_timeout(addr: impl ToSocketAddrs, timeout: Duration) -> Result<Self> {
+        connect_timeout(addr, timeout).map(Self::from_transport)
+    }
+
+    /// Create a [`ConnectionBuilder`] to configure a connection before
+    /// connecting
+    pub fn builder() -> ConnectionBuilder {
+        ConnectionBuilder::new()
+    }
+
+    /// Create a new connection to the first of several server addresses which
+    /// answers a health check, failing over to the next healthy address
+    /// whenever sending or receiving fails
+    ///
+    /// A command which fails to send is sent again after failing over, but a
+    /// response which fails to arrive is lost and its error is returned. Use
+    /// [`Connection::on_failover`] to be notified of each failover
+    ///
+    /// An address fails the health check if connecting or answering takes
+    /// longer than 5 seconds
+    pub fn with_addresses(addresses: &[impl AsRef<str>]) -> Result<Self> {
+        let addresses = addresses
+            .iter()
+            .map(|address| address.as_ref().to_string())
+            .collect();
+        let (failover, stream) = Failover::connect(addresses, LineEnding::default())?;
+        let mut connection = Self::from_transport(stream);
+        connection.failover = Some(failover);
+        Ok(connection)
+    }
+
+    /// Sets a callback which is called with the new address and the error
+    /// which caused each failover, for connections created with
+    /// [`Connection::with_addresses`]
+    pub fn on_failover(&mut self, callback: impl FnMut(&str, &io::Error) + Send + 'static) {
+        if let Some(failover) = &mut self.failover {
+            failover.set_callback(Box::new(callback));
+        }
+    }
+
+    /// Returns the server address currently connected to, for connections
+    /// created with [`Connection::with_addresses`]
+    pub fn current_address(&self) -> Option<&str> {
+        self.failover.as_ref().map(Failover::current_address)
+    }
+
+    /// Create a new connection over any [`Transport`], such as an in-memory
+    /// pipe or a TLS wrapper, which is already connected to the server
+    pub fn from_transport(transport: impl Transport + 'static) -> Self {
+        Self::from_stream(Box::new(transport))
+    }
+
+    fn from_stream(stream: Box<dyn Transport>) -> Self {
+        Self {
+            stream: BufReader::new(stream),
+            last_operation: None,
+            edit_log: None,
+            undo_history: None,
+            journal: None,
+            parse_mode: ParseMode::default(),
+            profile: Profile::default(),
+            world_limits: None,
+            metrics: Metrics::default(),
+            line_ending: LineEnding::default(),
+            flow: Flow::default(),
+            unacknowledged_writes: 0,
+            failover: None,
+            cache: None,
+            write_buffer: Vec::new(),
+            write_capacity: 0,
+        }
+    }
+
+    /// Sets how long to wait for a server response before failing with
+    /// [`io::ErrorKind::TimedOut`], or `None` to wait indefinitely (default)
+    ///
+    /// A zero duration is rejected with [`io::ErrorKind::InvalidInput`]. Fails
+    /// with [`io::ErrorKind::Unsupported`] if the [`Transport`] has no timeouts
+    pub fn set_read_timeout(&mut self, timeout: Option<Duration>) -> Result<()> {
+        if let Some(failover) = &mut self.failover {
+            failover.read_timeout = timeout;
+        }
+        self.stream.get_mut().set_read_timeout(timeout)
+    }
+
+    /// Sets how long sending a command may block before failing with
+    /// [`io::ErrorKind::TimedOut`], or `None` to wait indefinitely (default)
+    ///
+    /// A zero duration is rejected with [`io::ErrorKind::InvalidInput`]. Fails
+    /// with [`io::ErrorKind::Unsupported`] if the [`Transport`] has no timeouts
+    pub fn set_write_timeout(&mut self, timeout: Option<Duration>) -> Result<()> {
+        if let Some(failover) = &mut self.failover {
+            failover.write_timeout = timeout;
+        }
+        self.stream.get_mut().set_write_timeout(timeout)
+    }
+
+    /// Sets whether small commands are sent immediately, rather than combined
+    /// into fewer packets (Nagle's algorithm). Defaults to `false`
+    ///
+    /// Enabling this lowers the latency of single requests. Fails with
+    /// [`io::ErrorKind::Unsupported`] if the [`Transport`] has no such setting
+    pub fn set_nodelay(&mut self, nodelay: bool) -> Result<()> {
+        if let Some(failover) = &mut self.failover {
+            failover.nodelay = nodelay;
+        }
+        self.stream.get_mut().set_nodelay(nodelay)
+    }
+
+    /// Sets how many bytes of commands are buffered before being written
+    /// together, or `0` to write each command immediately (default)
+    ///
+    /// Buffered commands are written before waiting for any response, by
+    /// [`Connection::flush`], and when the connection is dropped. Writing
+    /// large builds in fewer, larger writes lowers system call overhead
+    pub fn set_write_buffer(&mut self, capacity: usize) -> Result<()> {
+        self.write_capacity = capacity;
+        if self.write_buffer.len() >= capacity {
+            self.flush()?;
+        }
+        self.write_buffer
+            .reserve(capacity.saturating_sub(self.write_buffer.len()));
+        Ok(())
+    }
+
+    /// Write any buffered commands to the server. See
+    /// [`Connection::set_write_buffer`]
+    ///
+    /// If writing fails without failing over, the buffered commands are
+    /// discarded
+    pub fn flush(&mut self) -> Result<()> {
+        if self.write_buffer.is_empty() {
+            return Ok(());
+        }
+        let buffer = std::mem::take(&mut self.write_buffer);
+        let result = self.write_bytes(&buffer);
+        self.write_buffer = buffer;
+        self.write_buffer.clear();
+        result
+    }
+
+    /// Sets how unexpected data in server responses is treated. Defaults to
+    /// [`ParseMode::Strict`]
+    pub fn set_parse_mode(&mut self, mode: ParseMode) {
+        self.parse_mode = mode;
+    }
+
+    /// Sets which server API to speak. Defaults to [`Profile::Elci`]
+    pub fn set_profile(&mut self, profile: Profile) {
+        self.profile = profile;
+    }
+
+    /// Sets the line ending sent after each command. Defaults to
+    /// [`LineEnding::Lf`]
+    ///
+    /// Responses are accepted with either line ending regardless
+    pub fn set_line_ending(&mut self, line_ending: LineEnding) {
+        self.line_ending = line_ending;
+    }
+
+    /// Sets how block writes are paced against the server. Defaults to
+    /// [`Flow::Unpaced`]
+    pub fn set_flow(&mut self, flow: Flow) {
+        self.flow = flow;
+    }
+
+    /// Sets the vertical bounds of the world, or `None` to not check bounds
+    /// (default)
+    ///
+    /// With limits set, reading or writing a single block outside of them
+    /// fails with [`OutOfWorld`] before anything is sent. Cuboid writes are
+    /// clipped to the limits, and cuboid reads fail if any part is outside
+    pub fn set_world_limits(&mut self, limits: Option<WorldLimits>) {
+        self.world_limits = limits;
+    }
+
+    /// Returns the vertical bounds of the world, if set
+    pub fn world_limits(&self) -> Option<WorldLimits> {
+        self.world_limits
+    }
+
+    /// Fail with [`OutOfWorld`] if any `y`-value from `a` to `b` is outside of
+    /// the world limits
+    pub(crate) fn check_in_world(&self, a: Coordinate, b: Coordinate) -> Result<()> {
+        let Some(limits) = self.world_limits else {
+            return Ok(());
+        };
+        for y in [a.y, b.y] {
+            if !limits.contains(y) {
+                let error = OutOfWorld { y, limits };
+                return Err(io::Error::new(io::ErrorKind::InvalidInput, error));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns [`Timing`] statistics of the most recent bulk operation
+    /// ([`get_blocks`], [`get_blocks_at`], [`set_blocks`], [`set_blocks_at`]
+    /// or [`get_heights`]), if any
+    ///
+    /// [`get_blocks`]: Connection::get_blocks
+    /// [`get_blocks_at`]: Connection::get_blocks_at
+    /// [`set_blocks_at`]: Connection::set_blocks_at
+    /// [`set_blocks`]: Connection::set_blocks
+    /// [`get_heights`]: Connection::get_heights
+    pub fn last_operation_stats(&self) -> Option<Timing> {
+        self.last_operation
+    }
+
+    /// Returns command counts, bytes transferred and response latencies for
+    /// the connection
+    pub fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+
+    /// Clear all [`Metrics`] recorded so far
+    pub fn reset_metrics(&mut self) {
+        self.metrics = Metrics::default();
+    }
+
+    /// Record [`Timing`] statistics for a bulk operation started at `start`
+    fn record_operation(&mut self, start: Instant, items: usize) {
+        self.last_operation = Some(Timing {
+            items,
+            duration: start.elapsed(),
+        });
+    }
+
+    /// Start caching the responses of [`get_block`] and [`get_height`] for
+    /// `ttl`, discarding anything previously cached
+    ///
+    /// Writes made through this connection forget the cached blocks and
+    /// heights they cover. Changes made any other way, such as by players or
+    /// [`do_command`], are not seen until the cached response expires
+    ///
+    /// [`get_block`]: Connection::get_block
+    /// [`get_height`]: Connection::get_height
+    /// [`do_command`]: Connection::do_command
+    pub fn enable_cache(&mut self, ttl: Duration) {
+        self.cache = Some(ReadCache::new(ttl));
+    }
+
+    /// Stop caching responses and discard the cache
+    pub fn disable_cache(&mut self) {
+        self.cache = None;
+    }
+
+    /// Forget every cached response, keeping the cache enabled
+    pub fn clear_cache(&mut self) {
+        if let Some(cache) = &mut self.cache {
+            cache.clear();
+        }
+    }
+
+    /// Forget cached responses covered by a write to the cuboid from `a` to
+    /// `b`, if caching
+    pub(crate) fn invalidate_cache(&mut self, a: Coordinate, b: Coordinate) {
+        if let Some(cache) = &mut self.cache {
+            cache.invalidate(a, b);
+        }
+    }
+
+    /// Start recording block writes made through this connection into an
+    /// [`EditLog`], discarding any previously recorded edits
+    pub fn start_edit_log(&mut self) {
+        self.edit_log = Some(EditLog::new());
+    }
+
+    /// Returns the [`EditLog`] being recorded, if any
+    pub fn edit_log(&self) -> Option<&EditLog> {
+        self.edit_log.as_ref()
+    }
+
+    /// Stop recording block writes and return the recorded [`EditLog`], if any
+    pub fn take_edit_log(&mut self) -> Option<EditLog> {
+        self.edit_log.take()
+    }
+
+    /// Record a block write if an [`EditLog`] is being recorded
+    pub(crate) fn record_edit(&mut self, a: Coordinate, b: Coordinate, block: Block) {
+        if let Some(edit_log) = &mut self.edit_log {
+            edit_log.push(a, b, block);
+        }
+    }
+
+    /// Start recording the prior state of every [`set_block`] and
+    /// [`set_blocks`] call so they can be reverted with [`undo`], discarding
+    /// any previous history
+    ///
+    /// At most `limit` prior blocks are kept; the oldest writes are forgotten
+    /// past that. Capturing the prior state costs an extra request per write
+    ///
+    /// [`set_block`]: Connection::set_block
+    /// [`set_blocks`]: Connection::set_blocks
+    /// [`undo`]: Connection::undo
+    pub fn enable_undo(&mut self, limit: usize) {
+        self.undo_history = Some(UndoHistory::new(limit));
+    }
+
+    /// Stop recording writes for [`undo`] and discard the history
+    ///
+    /// [`undo`]: Connection::undo
+    pub fn disable_undo(&mut self) {
+        self.undo_history = None;
+    }
+
+    /// Reverts the last `count` recorded writes, most recent first
+    ///
+    /// Returns the amount of writes reverted, which is less than `count` if
+    /// the history runs out
+    pub fn undo(&mut self, count: usize) -> Result<usize> {
+        for i in 0..count {
+            let Some(entry) = self.undo_history.as_mut().and_then(UndoHistory::pop_undo) else {
+                return Ok(i);
+            };
+            self.restore(&entry)?;
+            if let Some(history) = &mut self.undo_history {
+                history.push_redo(entry);
+            }
+        }
+        Ok(count)
+    }
+
+    /// Re-applies the last `count` writes reverted by [`undo`], least recently
+    /// reverted last
+    ///
+    /// Returns the amount of writes re-applied, which is less than `count` if
+    /// there are not enough reverted writes. Any new write clears the writes
+    /// available to redo
+    ///
+    /// [`undo`]: Connection::undo
+    pub fn redo(&mut self, count: usize) -> Result<usize> {
+        for i in 0..count {
+            let Some(entry) = self.undo_history.as_mut().and_then(UndoHistory::pop_redo) else {
+                return Ok(i);
+            };
+            let (a, b) = entry.corners();
+            self.write_blocks(a, b, entry.block)?;
+            if let Some(history) = &mut self.undo_history {
+                history.push_undo(entry);
+            }
+        }
+        Ok(count)
+    }
+
+    /// Start appending the prior state of every [`set_block`] and
+    /// [`set_blocks`] call to a journal file, so the world can be restored
+    /// with [`restore_journal`] even if the program crashes
+    ///
+    /// Appends to the file if it already exists. Capturing the prior state
+    /// costs an extra request per write
+    ///
+    /// [`set_block`]: Connection::set_block
+    /// [`set_blocks`]: Connection::set_blocks
+    /// [`restore_journal`]: Connection::restore_journal
+    pub fn start_journal(&mut self, path: impl AsRef<Path>) -> Result<()> {
+        self.journal = Some(Journal::open(path)?);
+        Ok(())
+    }
+
+    /// Stop appending writes to the journal file
+    pub fn stop_journal(&mut self) {
+        self.journal = None;
+    }
+
+    /// Reverts every write recorded in a journal file, most recent first,
+    /// restoring the world to its state before the journal was started
+    ///
+    /// Records after a truncated or corrupted one are ignored. The file is
+    /// left in place; remove it once restored. Returns the amount of writes
+    /// reverted
+    pub fn restore_journal(&mut self, path: impl AsRef<Path>) -> Result<usize> {
+        let entries = Journal::read(path)?;
+        for entry in entries.iter().rev() {
+            self.restore(entry)?;
+        }
+        Ok(entries.len())
+    }
+
+    /// Outlines the cuboid specified by [`Coordinate`]s `a` and `b` (in any
+    /// order) with a [`Block`] along its 12 edges, returning a [`Preview`]
+    /// which restores the original blocks after `duration`
+    ///
+    /// The markers are not recorded in the undo history or journal
+    pub fn preview(
+        &mut self,
+        a: impl Into<Coordinate>,
+        b: impl Into<Coordinate>,
+        block: Block,
+        duration: Duration,
+    ) -> Result<Preview> {
+        let a = a.into();
+        let b = b.into();
+        let (min, max) = (a.min(b), a.max(b));
+
+        let mut edges: Vec<(Coordinate, Coordinate)> = Vec::new();
+        for (p, q) in [
+            (min.y, min.z),
+            (min.y, max.z),
+            (max.y, min.z),
+            (max.y, max.z),
+        ] {
+            edges.push((Coordinate::new(min.x, p, q), Coordinate::new(max.x, p, q)));
+        }
+        for (p, q) in [
+            (min.x, min.z),
+            (min.x, max.z),
+            (max.x, min.z),
+            (max.x, max.z),
+        ] {
+            edges.push((Coordinate::new(p, min.y, q), Coordinate::new(p, max.y, q)));
+        }
+        for (p, q) in [
+            (min.x, min.y),
+            (min.x, max.y),
+            (max.x, min.y),
+            (max.x, max.y),
+        ] {
+            edges.push((Coordinate::new(p, q, min.z), Coordinate::new(p, q, max.z)));
+        }
+        edges.sort_unstable_by_key(|(a, b)| (a.x, a.y, a.z, b.x, b.y, b.z));
+        edges.dedup();
+
+        let last_operation = self.last_operation;
+        let mut entries = Vec::with_capacity(edges.len());
+        for (a, b) in edges {
+            let before = self.get_blocks(a, b)?;
+            self.write_blocks(a, b, block)?;
+            entries.push(Entry { before, block });
+        }
+        self.last_operation = last_operation;
+        Ok(Preview::new(entries, duration))
+    }
+
+    /// Capture the prior state of a cuboid about to be written to, if undo
+    /// history or a journal is being recorded
+    pub(crate) fn record_undo(&mut self, a: Coordinate, b: Coordinate, block: Block) -> Result<()> {
+        if self.undo_history.is_none() && self.journal.is_none() {
+            return Ok(());
+        }
+        let last_operation = self.last_operation;
+        let before = self.get_blocks(a, b)?;
+        self.last_operation = last_operation;
+        self.push_undo(Entry { before, block })
+    }
+
+    /// Record a write whose prior state is already known, to the undo history
+    /// and journal if enabled
+    fn push_undo(&mut self, entry: Entry) -> Result<()> {
+        if let Some(journal) = &mut self.journal {
+            journal.append(&entry)?;
+        }
+        if let Some(history) = &mut self.undo_history {
+            history.push(entry);
+        }
+        Ok(())
+    }
+
+    /// Write back the prior state of a recorded write
+    pub(crate) fn restore(&mut self, entry: &Entry) -> Result<()> {
+        for item in entry.before.iter() {
+            let location = item.position_absolute();
+            self.write_blocks(location, location, item.block())?;
+        }
+        Ok(())
+    }
+
+    /// Set a cuboid to a [`Block`], without recording undo history
+    fn write_blocks(&mut self, a: Coordinate, b: Coordinate, block: Block) -> Result<()> {
+        let command = if a == b {
+            Command::new("world.setBlock").arg_coordinate(a)
+        } else {
+            Command::new("world.setBlocks")
+                .arg_coordinate(a)
+                .arg_coordinate(b)
+        };
+        self.send(command.arg_block(block))?;
+        self.record_edit(a, b, block);
+        self.invalidate_cache(a, b);
+        self.pace_writes()
+    }
+
+    /// Wait for the server to process every write sent so far, if enough
+    /// writes have been sent for the configured [`Flow`]
+    fn pace_writes(&mut self) -> Result<()> {
+        let Flow::Paced(interval) = self.flow else {
+            return Ok(());
+        };
+        self.unacknowledged_writes += 1;
+        if self.unacknowledged_writes < interval {
+            return Ok(());
+        }
+        // Responses are sent in order, so any response means every prior
+        // write has been processed
+        self.send(Command::new("world.getHeight").arg_int(0).arg_int(0))?;
+        self.recv()?;
+        self.unacknowledged_writes = 0;
+        Ok(())
     }
 
     /// Serialize and send a command to the server
     fn send(&mut self, command: Command) -> Result<()> {
-        self.stream.write_all(command.build().as_bytes())?;
+        let name = command.name().to_string();
+        let message = command.build(self.line_ending);
+        self.write_message(&message)?;
+        self.metrics.record_send(&name, message.len());
+        Ok(())
+    }
+
+    /// Serialize several commands and send them to the server in one write
+    pub(crate) fn send_all(&mut self, commands: Vec<Command>) -> Result<()> {
+        let mut message = String::new();
+        let mut sent = Vec::with_capacity(commands.len());
+        for command in commands {
+            let name = command.name().to_string();
+            let line = command.build(self.line_ending);
+            sent.push((name, line.len()));
+            message += &line;
+        }
+        self.write_message(&message)?;
+        for (name, bytes) in sent {
+            self.metrics.record_send(&name, bytes);
+        }
+        Ok(())
+    }
+
+    /// Buffer serialized commands, writing them to the server once the write
+    /// buffer is full
+    fn write_message(&mut self, message: &str) -> Result<()> {
+        self.write_buffer.extend_from_slice(message.as_bytes());
+        if self.write_buffer.len() >= self.write_capacity {
+            self.flush()?;
+        }
         Ok(())
     }
 
+    /// Write bytes to the server, failing over and writing again if failover
+    /// is configured
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<()> {
+        let stream = self.stream.get_mut();
+        let Err(error) = stream.write_all(bytes).and_then(|()| stream.flush()) else {
+            return Ok(());
+        };
+        if !self.fail_over(&error) {
+            return Err(timed_out(error));
+        }
+        let stream = self.stream.get_mut();
+        stream
+            .write_all(bytes)
+            .and_then(|()| stream.flush())
+            .map_err(timed_out)
+    }
+
     /// Receive and deserialize a response from the server
-    fn recv(&mut self) -> Result<Response> {
-        let mut reader = BufReader::new(&self.stream);
+    ///
+    /// The response is lost if the connection fails, even if failover is
+    /// configured
+    pub(crate) fn recv(&mut self) -> Result<Response> {
+        self.flush()?;
         let mut buffer = String::new();
-        reader.read_line(&mut buffer)?;
-        Ok(Response::new(buffer))
+        let result = self.stream.read_line(&mut buffer).and_then(|bytes| {
+            if bytes == 0 {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "server closed the connection",
+                ));
+            }
+            Ok(bytes)
+        });
+        let bytes = match result {
+            Ok(bytes) => bytes,
+            Err(error) => {
+                self.fail_over(&error);
+                return Err(timed_out(error));
+            }
+        };
+        self.unacknowledged_writes = 0;
+        self.metrics.record_recv(bytes);
+        Ok(Response::new(buffer, self.parse_mode))
+    }
+
+    /// Replace the stream with a connection to another server address, if
+    /// failover is configured
+    ///
+    /// Returns `true` if a healthy server was connected to
+    fn fail_over(&mut self, error: &io::Error) -> bool {
+        let Some(failover) = &mut self.failover else {
+            return false;
+        };
+        let Some(stream) = failover.reconnect(error, self.line_ending) else {
+            return false;
+        };
+        self.stream = BufReader::new(Box::new(stream));
+        self.clear_cache();
+        true
+    }
+
+    /// Send a command which is not otherwise wrapped, such as
+    /// `send_raw("world.getBlock", ["0", "64", "0"])`
+    ///
+    /// Arguments are separated by commas, and any line breaks in them are
+    /// replaced. Read the response of commands which respond with
+    /// [`Connection::recv_raw`]. An invalid command name is rejected with
+    /// [`io::ErrorKind::InvalidInput`]
+    pub fn send_raw(
+        &mut self,
+        name: &str,
+        args: impl IntoIterator<Item = impl AsRef<str>>,
+    ) -> Result<()> {
+        if !is_valid_command_name(name) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid command name `{name}`"),
+            ));
+        }
+        let command = args
+            .into_iter()
+            .fold(Command::new(name), |command, arg| command.arg_string(arg));
+        self.send(command)
+    }
+
+    /// Read the next response sent by the server, after a command sent with
+    /// [`Connection::send_raw`]
+    ///
+    /// Blocks until a response arrives, so only call it for commands which
+    /// respond
+    pub fn recv_raw(&mut self) -> Result<Response> {
+        self.recv()
+    }
+
+    /// Start a [`Batch`] of commands, which are sent together in one write
+    pub fn batch(&mut self) -> Batch<'_> {
+        Batch::new(self)
     }
 
     /// Sends a message to the in-game chat, does not require a joined player
@@ -56,9 +757,181 @@ impl Connection {
     ///
     /// [ELCI]: https://github.com/rozukke/elci
     pub fn do_command(&mut self, command: impl AsRef<str>) -> Result<()> {
+        if self.profile == Profile::Pi {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "in-game commands are not supported by Minecraft: Pi Edition",
+            ));
+        }
         self.send(Command::new("player.doCommand").arg_string(command))
     }
 
+    /// Causes an explosion of the specified `power` at a [`Coordinate`]
+    ///
+    /// For reference, TNT has a power of `4`, the maximum is `127`. Uses the
+    /// in-game `/summon` command with an instantly exploding creeper, so has
+    /// the same requirements as [`do_command`]
+    ///
+    /// [`do_command`]: Connection::do_command
+    pub fn detonate(&mut self, location: impl Into<Coordinate>, power: u8) -> Result<()> {
+        let location = location.into();
+        self.do_command(format!(
+            "summon creeper {} {} {} {{ExplosionRadius:{}b,Fuse:0s,ignited:1b}}",
+            location.x,
+            location.y,
+            location.z,
+            power.min(i8::MAX as u8),
+        ))
+    }
+
+    /// Gives a status [`Effect`] to the players matched by `target` (a player
+    /// name or selector such as `@a`) for `duration`, rounded up to a whole
+    /// second
+    ///
+    /// An `amplifier` of `0` is level I of the effect. Uses the in-game
+    /// `/effect` command, so has the same requirements as [`do_command`]
+    ///
+    /// [`do_command`]: Connection::do_command
+    pub fn give_effect(
+        &mut self,
+        target: impl AsRef<str>,
+        effect: Effect,
+        duration: Duration,
+        amplifier: u8,
+    ) -> Result<()> {
+        let seconds = duration.as_secs() + u64::from(duration.subsec_nanos() > 0);
+        self.do_command(format!(
+            "effect give {} {} {} {} true",
+            target.as_ref(),
+            effect.id(),
+            seconds.min(i32::MAX as u64),
+            amplifier,
+        ))
+    }
+
+    /// Removes every status effect from the players matched by `target` (a
+    /// player name or selector such as `@a`)
+    ///
+    /// Uses the in-game `/effect` command, so has the same requirements as
+    /// [`do_command`]
+    ///
+    /// [`do_command`]: Connection::do_command
+    pub fn clear_effects(&mut self, target: impl AsRef<str>) -> Result<()> {
+        self.do_command(format!("effect clear {}", target.as_ref()))
+    }
+
+    /// Places a command block at the specified [`Coordinate`], containing
+    /// `command`
+    ///
+    /// Uses the in-game `/setblock` command, so has the same requirements as
+    /// [`do_command`]
+    ///
+    /// [`do_command`]: Connection::do_command
+    pub fn set_command_block(
+        &mut self,
+        location: impl Into<Coordinate>,
+        command: impl AsRef<str>,
+        mode: CommandBlockMode,
+        conditional: bool,
+        always_active: bool,
+    ) -> Result<()> {
+        let location = location.into();
+        let state = format!("conditional={}", conditional);
+        // Facing north by default
+        let modifier = 2 | i32::from(conditional) << 3;
+        self.place_command_block(
+            location,
+            command.as_ref(),
+            mode,
+            &state,
+            modifier,
+            always_active,
+        )
+    }
+
+    /// Lays out a sequence of command blocks starting at `start`, each one
+    /// `step` away from the previous and pointing into the next
+    ///
+    /// The first command block has the specified `mode` and needs to be
+    /// activated (unless it is [`CommandBlockMode::Repeat`]), the rest are
+    /// always active [`CommandBlockMode::Chain`] blocks. `step` must be one
+    /// block along a single axis, otherwise nothing is placed and
+    /// [`io::ErrorKind::InvalidInput`] is returned
+    pub fn set_command_chain(
+        &mut self,
+        start: impl Into<Coordinate>,
+        step: impl Into<Coordinate>,
+        mode: CommandBlockMode,
+        commands: &[impl AsRef<str>],
+    ) -> Result<()> {
+        let step = step.into();
+        let (facing, modifier) = match (step.x, step.y, step.z) {
+            (1, 0, 0) => ("east", 5),
+            (-1, 0, 0) => ("west", 4),
+            (0, 1, 0) => ("up", 1),
+            (0, -1, 0) => ("down", 0),
+            (0, 0, 1) => ("south", 3),
+            (0, 0, -1) => ("north", 2),
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "step must be one block along a single axis",
+                ))
+            }
+        };
+        let state = format!("facing={}", facing);
+
+        let mut location = start.into();
+        for (i, command) in commands.iter().enumerate() {
+            let (mode, always_active) = if i == 0 {
+                (mode, mode == CommandBlockMode::Repeat)
+            } else {
+                (CommandBlockMode::Chain, true)
+            };
+            self.place_command_block(
+                location,
+                command.as_ref(),
+                mode,
+                &state,
+                modifier,
+                always_active,
+            )?;
+            location = location + step;
+        }
+        Ok(())
+    }
+
+    /// Places a command block with the given block state using `/setblock`
+    ///
+    /// `modifier` is the legacy equivalent of `state`, recorded for undo and the
+    /// edit log
+    fn place_command_block(
+        &mut self,
+        location: Coordinate,
+        command: &str,
+        mode: CommandBlockMode,
+        state: &str,
+        modifier: i32,
+        always_active: bool,
+    ) -> Result<()> {
+        let block = Block::new(mode.block().id, modifier);
+        self.record_undo(location, location, block)?;
+        let command = command.replace('\\', "\\\\").replace('"', "\\\"");
+        self.do_command(format!(
+            "setblock {} {} {} {}[{}]{{Command:\"{}\",auto:{}b}} replace",
+            location.x,
+            location.y,
+            location.z,
+            mode.block_name(),
+            state,
+            command,
+            always_active as u8,
+        ))?;
+        self.record_edit(location, location, block);
+        self.invalidate_cache(location, location);
+        Ok(())
+    }
+
     /// Sets player position (block position of lower half of playermodel) to
     /// specified [`Coordinate`]
     pub fn set_player_position(&mut self, position: impl Into<Coordinate>) -> Result<()> {
@@ -78,8 +951,33 @@ impl Connection {
     pub fn get_player_position(&mut self) -> Result<Coordinate> {
         self.send(Command::new("player.getPos"))?;
         let response = self.recv()?;
-        let coord = response.as_coordinate().expect("malformed server response");
-        Ok(coord)
+        response.as_coordinate().ok_or_else(|| malformed(&response))
+    }
+
+    /// Moves the player in a straight line to the specified [`Coordinate`],
+    /// taking `speed` steps per second instead of teleporting
+    ///
+    /// Each step moves the player at most one block along each axis. Blocks
+    /// the current thread until the player reaches the target. A `speed`
+    /// which is not positive, or too small to give a step interval, is
+    /// rejected with [`io::ErrorKind::InvalidInput`]
+    pub fn walk_player_to(&mut self, target: impl Into<Coordinate>, speed: f32) -> Result<()> {
+        if !(speed > 0.0 && speed.is_finite()) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "speed must be positive",
+            ));
+        }
+        let interval = Duration::try_from_secs_f32(1.0 / speed)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))?;
+        let target = target.into();
+        let start = self.get_player_position()?;
+
+        for position in start.line_to(target).into_iter().skip(1) {
+            thread::sleep(interval);
+            self.set_player_position(position)?;
+        }
+        Ok(())
     }
 
     /// Returns the coordinate location of the block the player is standing on
@@ -92,21 +990,77 @@ impl Connection {
 
     /// Sets block at [`Coordinate`] to specified [`Block`]
     pub fn set_block(&mut self, location: impl Into<Coordinate>, block: Block) -> Result<()> {
-        self.send(
-            Command::new("world.setBlock")
-                .arg_coordinate(location.into())
-                .arg_block(block),
-        )
+        let location = location.into();
+        self.check_in_world(location, location)?;
+        self.record_undo(location, location, block)?;
+        self.write_blocks(location, location, block)
     }
 
     /// Returns [`Block`] object from specified [`Coordinate`]
     pub fn get_block(&mut self, location: impl Into<Coordinate>) -> Result<Block> {
-        self.send(Command::new("world.getBlockWithData").arg_coordinate(location.into()))?;
+        let location = location.into();
+        self.check_in_world(location, location)?;
+        if let Some(block) = self.cache.as_ref().and_then(|cache| cache.block(location)) {
+            return Ok(block);
+        }
+        self.send(self.block_query(location))?;
         let response = self.recv()?;
-        let block = response.as_block().expect("malformed server response");
+        let block = self.parse_block_response(&response)?;
+        if let Some(cache) = &mut self.cache {
+            cache.insert_block(location, block);
+        }
         Ok(block)
     }
 
+    /// Create the command to query the [`Block`] at a [`Coordinate`]
+    pub(crate) fn block_query(&self, location: Coordinate) -> Command {
+        match self.profile {
+            Profile::Pi => Command::new("world.getBlock").arg_coordinate(location),
+            Profile::Elci => Command::new("world.getBlockWithData").arg_coordinate(location),
+        }
+    }
+
+    /// Parse the response to a [`Connection::block_query`]
+    pub(crate) fn parse_block_response(&self, response: &Response) -> Result<Block> {
+        let block = match self.profile {
+            Profile::Pi => response.as_integer().map(|id| Block::new(id, 0)),
+            Profile::Elci => response.as_block(),
+        };
+        block.ok_or_else(|| malformed(response))
+    }
+
+    /// Returns the [`Block`] at each of several scattered [`Coordinate`]s, in
+    /// the same order
+    ///
+    /// Requests are sent in large groups before reading their responses,
+    /// rather than waiting for each response in turn like [`get_block`]
+    ///
+    /// [`get_block`]: Connection::get_block
+    pub fn get_blocks_at(&mut self, locations: &[Coordinate]) -> Result<Vec<Block>> {
+        for location in locations {
+            self.check_in_world(*location, *location)?;
+        }
+        let start = Instant::now();
+        let mut blocks = Vec::with_capacity(locations.len());
+        for window in locations.chunks(PIPELINE_WINDOW) {
+            let commands = window
+                .iter()
+                .map(|location| self.block_query(*location))
+                .collect();
+            self.send_all(commands)?;
+            for location in window {
+                let response = self.recv()?;
+                let block = self.parse_block_response(&response)?;
+                if let Some(cache) = &mut self.cache {
+                    cache.insert_block(*location, block);
+                }
+                blocks.push(block);
+            }
+        }
+        self.record_operation(start, locations.len());
+        Ok(blocks)
+    }
+
     /// Sets a cuboid of blocks to all be the specified [`Block`], with the
     /// corners of the cuboid specified by [`Coordinate`]s `a` and `b` (in any
     /// order)
@@ -116,12 +1070,45 @@ impl Connection {
         b: impl Into<Coordinate>,
         block: Block,
     ) -> Result<()> {
-        self.send(
-            Command::new("world.setBlocks")
-                .arg_coordinate(a.into())
-                .arg_coordinate(b.into())
-                .arg_block(block),
-        )
+        let mut a = a.into();
+        let mut b = b.into();
+        if let Some(limits) = self.world_limits {
+            let (low, high) = (a.y.min(b.y), a.y.max(b.y));
+            if high < limits.min_y || low > limits.max_y {
+                let y = if high < limits.min_y { high } else { low };
+                let error = OutOfWorld { y, limits };
+                return Err(io::Error::new(io::ErrorKind::InvalidInput, error));
+            }
+            a.y = low.max(limits.min_y);
+            b.y = high.min(limits.max_y);
+        }
+        self.record_undo(a, b, block)?;
+        let start = Instant::now();
+        self.write_blocks(a, b, block)?;
+        self.record_operation(start, a.size_between(b).volume());
+        Ok(())
+    }
+
+    /// Sets the [`Block`] at each of several scattered [`Coordinate`]s
+    ///
+    /// Every command is sent in one write, like a [`Batch`], rather than one
+    /// write per block like [`set_block`]. If any coordinate is outside of the
+    /// world limits, nothing is sent
+    ///
+    /// [`set_block`]: Connection::set_block
+    pub fn set_blocks_at(
+        &mut self,
+        blocks: impl IntoIterator<Item = (Coordinate, Block)>,
+    ) -> Result<()> {
+        let start = Instant::now();
+        let mut batch = self.batch();
+        for (location, block) in blocks {
+            batch.set_block(location, block);
+        }
+        let count = batch.len();
+        batch.send()?;
+        self.record_operation(start, count);
+        Ok(())
     }
 
     /// Returns a 3D `Vec` of the [`Block`]s of cuboid specified by
@@ -133,17 +1120,56 @@ impl Connection {
     ) -> Result<Chunk> {
         let a = a.into();
         let b = b.into();
-        self.send(
-            Command::new("world.getBlocksWithData")
-                .arg_coordinate(a)
-                .arg_coordinate(b),
-        )?;
-        let response = self.recv()?;
-        let list = response.as_block_list().expect("malformed server response");
+        self.check_in_world(a, b)?;
+        let start = Instant::now();
+        let list = if self.profile == Profile::Pi {
+            let origin = a.min(b);
+            let size = a.size_between(b);
+            (0..size.volume())
+                .map(|index| self.get_block(size.index_to_coordinate(index) + origin))
+                .collect::<Result<Vec<_>>>()?
+        } else {
+            self.send(
+                Command::new("world.getBlocksWithData")
+                    .arg_coordinate(a)
+                    .arg_coordinate(b),
+            )?;
+            let response = self.recv()?;
+            response
+                .as_block_list()
+                .filter(|list| list.len() == a.size_between(b).volume())
+                .ok_or_else(|| malformed(&response))?
+        };
+        self.record_operation(start, list.len());
         let chunk = Chunk::new(a, b, list);
         Ok(chunk)
     }
 
+    /// Calls `visitor` with the **absolute** [`Coordinate`] and [`Block`] of
+    /// each block in the cuboid specified by [`Coordinate`]s `a` and `b` (in
+    /// any order), until it returns [`ControlFlow::Break`]
+    ///
+    /// The cuboid is fetched one horizontal layer at a time, from the bottom
+    /// up, so no further layers are requested once the visitor breaks. Returns
+    /// the value of the break, if any
+    pub fn scan_region<B>(
+        &mut self,
+        a: impl Into<Coordinate>,
+        b: impl Into<Coordinate>,
+        mut visitor: impl FnMut(Coordinate, Block) -> ControlFlow<B>,
+    ) -> Result<Option<B>> {
+        let a = a.into();
+        let b = b.into();
+        let (min, max) = (a.min(b), a.max(b));
+        for y in min.y..=max.y {
+            let layer = self.get_blocks([min.x, y, min.z], [max.x, y, max.z])?;
+            if let Some(value) = layer.visit(&mut visitor) {
+                return Ok(Some(value));
+            }
+        }
+        Ok(None)
+    }
+
     /// Returns the `y`-value of the highest solid block at the specified `x`
     /// and `z` coordinate
     ///
@@ -153,9 +1179,15 @@ impl Connection {
     ///
     /// [`get_heights`]: Connection::get_heights
     pub fn get_height(&mut self, x: i32, z: i32) -> Result<i32> {
+        if let Some(height) = self.cache.as_ref().and_then(|cache| cache.height(x, z)) {
+            return Ok(height);
+        }
         self.send(Command::new("world.getHeight").arg_int(x).arg_int(z))?;
         let response = self.recv()?;
-        let height = response.as_integer().expect("malformed server response");
+        let height = response.as_integer().ok_or_else(|| malformed(&response))?;
+        if let Some(cache) = &mut self.cache {
+            cache.insert_height(x, z, height);
+        }
         Ok(height)
     }
 
@@ -170,16 +1202,785 @@ impl Connection {
     ) -> Result<HeightMap> {
         let a = a.into();
         let b = b.into();
-        self.send(
-            Command::new("world.getHeights")
-                .arg_int(a.x)
-                .arg_int(a.z)
-                .arg_int(b.x)
-                .arg_int(b.z),
-        )?;
-        let response = self.recv()?;
-        let list = response.as_integer_list();
+        let start = Instant::now();
+        let list = if self.profile == Profile::Pi {
+            let origin = a.min(b);
+            let size = a.size_between(b);
+            let mut list = Vec::new();
+            for x in 0..size.x as i32 {
+                for z in 0..size.z as i32 {
+                    list.push(self.get_height(origin.x + x, origin.z + z)?);
+                }
+            }
+            list
+        } else {
+            self.send(
+                Command::new("world.getHeights")
+                    .arg_int(a.x)
+                    .arg_int(a.z)
+                    .arg_int(b.x)
+                    .arg_int(b.z),
+            )?;
+            let response = self.recv()?;
+            let list = response.as_integer_list();
+            let size = a.size_between(b);
+            if list.len() != size.x as usize * size.z as usize {
+                return Err(malformed(&response));
+            }
+            list
+        };
+        self.record_operation(start, list.len());
         let height_map = HeightMap::new(a, b, list);
         Ok(height_map)
     }
+
+    /// Places a layer of `thickness` blocks on top of the terrain surface for
+    /// every column in the area specified by [`Coordinate`]s `a` and `b` (in
+    /// any order, `y`-values are ignored)
+    ///
+    /// If `clear_vegetation` is `true`, vegetation (see
+    /// [`Block::is_vegetation`]) standing on the terrain is cleared first, so
+    /// the layer is placed on the ground rather than on top of plants and trees
+    pub fn carpet_region(
+        &mut self,
+        a: impl Into<Coordinate>,
+        b: impl Into<Coordinate>,
+        block: Block,
+        thickness: u32,
+        clear_vegetation: bool,
+    ) -> Result<()> {
+        if thickness == 0 {
+            return Ok(());
+        }
+        let a = a.into();
+        let b = b.into();
+        let mut height_map = self.get_heights(a, b)?;
+        if clear_vegetation {
+            // Heights include vegetation, so clear between the lowest and
+            // highest surface until only terrain is left at the surface
+            while let Some((low, high)) = height_range(&height_map) {
+                let removed = self.clear_vegetation(
+                    Coordinate::new(a.x, low, a.z),
+                    Coordinate::new(b.x, high, b.z),
+                )?;
+                if removed == 0 {
+                    break;
+                }
+                height_map = self.get_heights(a, b)?;
+            }
+        }
+        for item in height_map.iter() {
+            let mut bottom = item.position_absolute();
+            bottom.y = item.height() + 1;
+            let mut top = bottom;
+            top.y += thickness as i32 - 1;
+            self.set_blocks(bottom, top, block)?;
+        }
+        Ok(())
+    }
+
+    /// Levels the terrain of the area specified by [`Coordinate`]s `a` and `b`
+    /// (in any order, `y`-values are ignored) to have its surface at
+    /// `target_y`
+    ///
+    /// Columns below `target_y` are filled up with `fill_block`. If
+    /// `clear_above` is `true`, columns above `target_y` are cut down with
+    /// air. `progress` is called with the amount of columns processed so far
+    /// and the total amount of columns
+    pub fn flatten_region(
+        &mut self,
+        a: impl Into<Coordinate>,
+        b: impl Into<Coordinate>,
+        target_y: i32,
+        fill_block: Block,
+        clear_above: bool,
+        mut progress: impl FnMut(usize, usize),
+    ) -> Result<()> {
+        let height_map = self.get_heights(a, b)?;
+        let total = height_map.iter().count();
+        for (i, item) in height_map.iter().enumerate() {
+            let column = item.position_absolute();
+            let height = item.height();
+            if height < target_y {
+                self.set_blocks(
+                    Coordinate::new(column.x, height + 1, column.z),
+                    Coordinate::new(column.x, target_y, column.z),
+                    fill_block,
+                )?;
+            } else if clear_above && height > target_y {
+                self.set_blocks(
+                    Coordinate::new(column.x, target_y + 1, column.z),
+                    Coordinate::new(column.x, height, column.z),
+                    Block::AIR,
+                )?;
+            }
+            progress(i + 1, total);
+        }
+        Ok(())
+    }
+
+    /// Replaces every vegetation block (see [`Block::is_vegetation`]) in the
+    /// cuboid specified by [`Coordinate`]s `a` and `b` (in any order) with air,
+    /// leaving terrain intact
+    ///
+    /// The cuboid is read and cleared in tiles of at most 32×32×32 blocks, so
+    /// memory use does not grow with its size. Returns the amount of blocks
+    /// removed
+    pub fn clear_vegetation(
+        &mut self,
+        a: impl Into<Coordinate>,
+        b: impl Into<Coordinate>,
+    ) -> Result<usize> {
+        self.clear_matching(a, b, |_, block| block.is_vegetation())
+    }
+
+    /// Replaces every flowing or still water and lava block in the cuboid
+    /// specified by [`Coordinate`]s `a` and `b` (in any order) with air
+    ///
+    /// The cuboid is read and cleared in tiles of at most 32×32×32 blocks, so
+    /// memory use does not grow with its size. Returns the amount of blocks
+    /// removed
+    pub fn drain_liquids(
+        &mut self,
+        a: impl Into<Coordinate>,
+        b: impl Into<Coordinate>,
+    ) -> Result<usize> {
+        self.clear_matching(a, b, |_, block| block.is_liquid())
+    }
+
+    /// Replaces every block matching `predicate` in the cuboid specified by
+    /// [`Coordinate`]s `a` and `b` (in any order) with air
+    ///
+    /// `predicate` is given the **absolute** [`Coordinate`] and the [`Block`].
+    /// The cuboid is read one tile of at most [`CLEAR_TILE`] blocks along each
+    /// axis at a time, so memory use does not grow with its size. Matching
+    /// blocks next to each other along `z` are cleared with a single write
+    fn clear_matching(
+        &mut self,
+        a: impl Into<Coordinate>,
+        b: impl Into<Coordinate>,
+        predicate: impl Fn(Coordinate, &Block) -> bool,
+    ) -> Result<usize> {
+        let a = a.into();
+        let b = b.into();
+        let (min, max) = (a.min(b), a.max(b));
+        let step = CLEAR_TILE as usize;
+        let end = |start: i32, max: i32| start.saturating_add(CLEAR_TILE - 1).min(max);
+
+        let mut count = 0;
+        for tile_y in (min.y..=max.y).step_by(step) {
+            for tile_x in (min.x..=max.x).step_by(step) {
+                for tile_z in (min.z..=max.z).step_by(step) {
+                    let tile_min = Coordinate::new(tile_x, tile_y, tile_z);
+                    let tile_max =
+                        Coordinate::new(end(tile_x, max.x), end(tile_y, max.y), end(tile_z, max.z));
+                    let tile = self.get_blocks(tile_min, tile_max)?;
+                    for y in tile_min.y..=tile_max.y {
+                        for x in tile_min.x..=tile_max.x {
+                            let mut run = None;
+                            for z in tile_min.z..=tile_max.z {
+                                let location = Coordinate::new(x, y, z);
+                                let block = tile
+                                    .get(location - tile_min)
+                                    .expect("location should be within tile");
+                                if predicate(location, &block) {
+                                    run.get_or_insert(location);
+                                } else if let Some(start) = run.take() {
+                                    count += self.clear_run(&tile, start, location - [0, 0, 1])?;
+                                }
+                            }
+                            if let Some(start) = run {
+                                let location = Coordinate::new(x, y, tile_max.z);
+                                count += self.clear_run(&tile, start, location)?;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Ok(count)
+    }
+
+    /// Replace a run of blocks along `z` within a tile read by
+    /// [`Connection::clear_matching`] with air, returning the amount of blocks
+    ///
+    /// The prior state is taken from the tile, rather than read again
+    fn clear_run(&mut self, tile: &Chunk, start: Coordinate, end: Coordinate) -> Result<usize> {
+        let length = (end.z - start.z + 1) as usize;
+        if self.undo_history.is_some() || self.journal.is_some() {
+            let list = (start.z..=end.z)
+                .map(|z| {
+                    tile.get(Coordinate::new(start.x, start.y, z) - tile.origin())
+                        .expect("run should be within tile")
+                })
+                .collect();
+            let before = Chunk::new(start, end, list);
+            self.push_undo(Entry {
+                before,
+                block: Block::AIR,
+            })?;
+        }
+        self.write_blocks(start, end, Block::AIR)?;
+        Ok(length)
+    }
+
+    /// Builds a path of `material` along the terrain surface, through each of
+    /// the `waypoints` in order (`y`-values are ignored)
+    ///
+    /// The path is `width` blocks wide and replaces the top block of each
+    /// column it covers. Where the surface rises by one block along the path,
+    /// the matching stairs of `material` are placed instead, if there are any
+    /// (see [`block_state::stairs_id`])
+    pub fn build_path(
+        &mut self,
+        waypoints: &[Coordinate],
+        width: u32,
+        material: Block,
+    ) -> Result<()> {
+        let Some(first) = waypoints.first() else {
+            return Ok(());
+        };
+        if width == 0 {
+            return Ok(());
+        }
+        let radius_low = (width as i32 - 1) / 2;
+        let radius_high = width as i32 / 2;
+
+        // Direction of travel of each column, along the main axis of its segment
+        let mut columns = HashMap::new();
+        let segments = waypoints.windows(2).flat_map(|pair| {
+            let a = Coordinate::new(pair[0].x, 0, pair[0].z);
+            let b = Coordinate::new(pair[1].x, 0, pair[1].z);
+            let facing = if (b.x - a.x).abs() >= (b.z - a.z).abs() {
+                Facing::East
+            } else {
+                Facing::South
+            };
+            a.line_to(b)
+                .into_iter()
+                .map(move |center| (center, Some(facing)))
+        });
+        for (center, facing) in segments.chain([(Coordinate::new(first.x, 0, first.z), None)]) {
+            for x in -radius_low..=radius_high {
+                for z in -radius_low..=radius_high {
+                    columns.entry(center + [x, 0, z]).or_insert(facing);
+                }
+            }
+        }
+
+        let Some(min) = columns.keys().copied().reduce(Coordinate::min) else {
+            return Ok(());
+        };
+        let max = columns.keys().copied().fold(min, Coordinate::max);
+        // Include the neighbors of the edge columns, to find slopes
+        let min = min - [1, 0, 1];
+        let height_map = self.get_heights(min, max + [1, 0, 1])?;
+        let height = |column: Coordinate| height_map.get(column - min);
+        let stairs = block_state::stairs_id(material);
+
+        for (column, facing) in columns {
+            let top = height(column).expect("column should be within height map");
+            let mut block = material;
+            if let (Some(stairs), Some(facing)) = (stairs, facing) {
+                let ahead = Coordinate::from(facing);
+                let ascent = if height(column - ahead) == Some(top - 1) {
+                    Some(facing)
+                } else if height(column + ahead) == Some(top - 1) {
+                    Some(facing.opposite())
+                } else {
+                    None
+                };
+                if let Some(facing) = ascent {
+                    block = StairState {
+                        facing,
+                        upside_down: false,
+                    }
+                    .to_block(stairs);
+                }
+            }
+            self.set_block(Coordinate::new(column.x, top, column.z), block)?;
+        }
+        Ok(())
+    }
+
+    /// Builds a bridge in a straight line from `a` to `b`, with pillars
+    /// reaching down to the terrain surface
+    ///
+    /// The deck is centered on the line between `a` and `b`, sloping evenly if
+    /// their `y`-values differ
+    pub fn build_bridge(
+        &mut self,
+        a: impl Into<Coordinate>,
+        b: impl Into<Coordinate>,
+        style: BridgeStyle,
+    ) -> Result<()> {
+        let a = a.into();
+        let b = b.into();
+        if style.width == 0 {
+            return Ok(());
+        }
+        let left = -(style.width as i32 - 1) / 2;
+        let right = style.width as i32 / 2;
+        // Extend the deck sideways along whichever horizontal axis is not the
+        // main direction of the bridge
+        let side = if (b.x - a.x).abs() >= (b.z - a.z).abs() {
+            Coordinate::new(0, 0, 1)
+        } else {
+            Coordinate::new(1, 0, 0)
+        };
+        let offset =
+            |center: Coordinate, distance: i32| center + [side.x * distance, 0, side.z * distance];
+
+        let height_map = self.get_heights(offset(a, left - 1), offset(b, right + 1))?;
+        let origin = height_map.origin();
+
+        for (i, center) in a.line_to(b).into_iter().enumerate() {
+            // Railings stand on an extra row of deck along each edge
+            let (edge_left, edge_right) = match style.railing {
+                Some(_) => (left - 1, right + 1),
+                None => (left, right),
+            };
+            for distance in edge_left..=edge_right {
+                self.set_block(offset(center, distance), style.deck)?;
+            }
+            if let Some(railing) = style.railing {
+                self.set_block(offset(center, edge_left) + [0, 1, 0], railing)?;
+                self.set_block(offset(center, edge_right) + [0, 1, 0], railing)?;
+            }
+
+            if style.pillar_spacing > 0 && i % style.pillar_spacing as usize == 0 {
+                let ground = height_map
+                    .get(Coordinate::new(center.x - origin.x, 0, center.z - origin.z))
+                    .expect("bridge should be within height map");
+                if ground < center.y - 1 {
+                    self.set_blocks(
+                        Coordinate::new(center.x, ground + 1, center.z),
+                        center - [0, 1, 0],
+                        style.pillar,
+                    )?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Carves caves out of the cuboid specified by [`Coordinate`]s `a` and `b`
+    /// (in any order), replacing every block where the noise exceeds
+    /// `threshold` with air
+    ///
+    /// Existing air is left untouched. The cuboid is read and cleared in tiles
+    /// of at most 32×32×32 blocks, as with [`Connection::drain_liquids`].
+    /// Returns the amount of blocks removed
+    pub fn carve_caves(
+        &mut self,
+        a: impl Into<Coordinate>,
+        b: impl Into<Coordinate>,
+        noise: NoiseParams,
+        threshold: f32,
+    ) -> Result<usize> {
+        self.clear_matching(a, b, |location, block| {
+            *block != Block::AIR && noise.sample(location) > threshold
+        })
+    }
+
+    /// Fills every column inside a [`Polygon`] with a single `block`, either at
+    /// a fixed height or on top of the terrain surface
+    pub fn polygon_fill(
+        &mut self,
+        polygon: &Polygon,
+        block: Block,
+        height: FillHeight,
+    ) -> Result<()> {
+        let Some(bounds) = polygon.bounds() else {
+            return Ok(());
+        };
+        let height_map = match height {
+            FillHeight::At(_) => None,
+            FillHeight::Surface => Some(self.get_heights(bounds.min, bounds.max)?),
+        };
+        for column in polygon.columns() {
+            let y = match height {
+                FillHeight::At(y) => y,
+                FillHeight::Surface => {
+                    let surface = height_map
+                        .as_ref()
+                        .and_then(|height_map| height_map.get(column - bounds.min))
+                        .expect("column should be within height map");
+                    surface + 1
+                }
+            };
+            self.set_block(Coordinate::new(column.x, y, column.z), block)?;
+        }
+        Ok(())
+    }
+
+    /// Places every block of a [`Chunk`], with its anchor (or its origin, if
+    /// no anchor is set) at the specified [`Coordinate`]
+    ///
+    /// See [`Chunk::anchor`]
+    pub fn paste(&mut self, chunk: &Chunk, position: impl Into<Coordinate>) -> Result<()> {
+        self.paste_with_progress(chunk, position.into(), |_, _| ())
+    }
+
+    /// Captures the cuboid specified by [`Coordinate`]s `a` and `b` (in any
+    /// order), flips it along an [`Axis`] and places it with its origin at
+    /// `target`
+    ///
+    /// `progress` is called with the amount of blocks placed so far and the
+    /// total amount of blocks. See [`Chunk::mirror`] for caveats
+    pub fn mirror_region(
+        &mut self,
+        a: impl Into<Coordinate>,
+        b: impl Into<Coordinate>,
+        axis: Axis,
+        target: impl Into<Coordinate>,
+        progress: impl FnMut(usize, usize),
+    ) -> Result<()> {
+        let chunk = self.get_blocks(a, b)?.mirror(axis);
+        self.paste_with_progress(&chunk, target.into(), progress)
+    }
+
+    fn paste_with_progress(
+        &mut self,
+        chunk: &Chunk,
+        position: Coordinate,
+        mut progress: impl FnMut(usize, usize),
+    ) -> Result<()> {
+        let origin = position - chunk.pivot();
+        let total = chunk.size().volume();
+        for (i, item) in chunk.iter().enumerate() {
+            self.set_block(item.position_relative() + origin, item.block())?;
+            progress(i + 1, total);
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        // Errors cannot be reported here; call `flush` to handle them
+        let _ = self.flush();
+    }
+}
+
+impl ConnectionBuilder {
+    /// Create a builder with the default configuration, connecting to
+    /// [`Connection::DEFAULT_ADDRESS`]
+    pub fn new() -> Self {
+        Self {
+            address: Connection::DEFAULT_ADDRESS.to_string(),
+            connect_timeout: None,
+            read_timeout: None,
+            write_timeout: None,
+            retries: 0,
+            retry_delay: Duration::ZERO,
+            parse_mode: ParseMode::default(),
+            profile: Profile::default(),
+            line_ending: LineEnding::default(),
+            flow: Flow::default(),
+            world_limits: None,
+            nodelay: false,
+            write_buffer: 0,
+        }
+    }
+
+    /// Sets the server address, such as `"127.0.0.1:4711"`
+    pub fn address(mut self, address: impl Into<String>) -> Self {
+        self.address = address.into();
+        self
+    }
+
+    /// Sets how long each connection attempt may take. See
+    /// [`Connection::with_timeout`]
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// See [`Connection::set_read_timeout`]
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = Some(timeout);
+        self
+    }
+
+    /// See [`Connection::set_write_timeout`]
+    pub fn write_timeout(mut self, timeout: Duration) -> Self {
+        self.write_timeout = Some(timeout);
+        self
+    }
+
+    /// See [`Connection::set_nodelay`]
+    pub fn nodelay(mut self, nodelay: bool) -> Self {
+        self.nodelay = nodelay;
+        self
+    }
+
+    /// See [`Connection::set_write_buffer`]
+    pub fn write_buffer(mut self, capacity: usize) -> Self {
+        self.write_buffer = capacity;
+        self
+    }
+
+    /// Sets how many times to retry connecting after a failed attempt, waiting
+    /// `delay` between attempts. Defaults to no retries
+    pub fn retries(mut self, retries: u32, delay: Duration) -> Self {
+        self.retries = retries;
+        self.retry_delay = delay;
+        self
+    }
+
+    /// See [`Connection::set_parse_mode`]
+    pub fn parse_mode(mut self, mode: ParseMode) -> Self {
+        self.parse_mode = mode;
+        self
+    }
+
+    /// See [`Connection::set_profile`]
+    pub fn profile(mut self, profile: Profile) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// See [`Connection::set_line_ending`]
+    pub fn line_ending(mut self, line_ending: LineEnding) -> Self {
+        self.line_ending = line_ending;
+        self
+    }
+
+    /// See [`Connection::set_flow`]
+    pub fn flow(mut self, flow: Flow) -> Self {
+        self.flow = flow;
+        self
+    }
+
+    /// See [`Connection::set_world_limits`]
+    pub fn world_limits(mut self, limits: WorldLimits) -> Self {
+        self.world_limits = Some(limits);
+        self
+    }
+
+    /// Connect to the server with the configuration
+    ///
+    /// Returns the error of the last attempt if every attempt fails
+    pub fn connect(&self) -> Result<Connection> {
+        let mut attempt = 0;
+        let stream = loop {
+            let result = match self.connect_timeout {
+                Some(timeout) => connect_timeout(self.address.as_str(), timeout),
+                None => TcpStream::connect(self.address.as_str()),
+            };
+            match result {
+                Ok(stream) => break stream,
+                Err(error) if attempt >= self.retries => return Err(error),
+                Err(_) => {
+                    attempt += 1;
+                    thread::sleep(self.retry_delay);
+                }
+            }
+        };
+
+        let mut connection = Connection::from_transport(stream);
+        connection.set_read_timeout(self.read_timeout)?;
+        connection.set_write_timeout(self.write_timeout)?;
+        connection.set_nodelay(self.nodelay)?;
+        connection.set_write_buffer(self.write_buffer)?;
+        connection.set_parse_mode(self.parse_mode);
+        connection.set_profile(self.profile);
+        connection.set_line_ending(self.line_ending);
+        connection.set_flow(self.flow);
+        connection.set_world_limits(self.world_limits);
+        Ok(connection)
+    }
+}
+
+impl Default for ConnectionBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Connect to the first address which `addr` resolves to that accepts a
+/// connection within `timeout`
+pub(crate) fn connect_timeout(addr: impl ToSocketAddrs, timeout: Duration) -> Result<TcpStream> {
+    let mut last_error = None;
+    for addr in addr.to_socket_addrs()? {
+        match TcpStream::connect_timeout(&addr, timeout) {
+            Ok(stream) => return Ok(stream),
+            Err(error) => last_error = Some(error),
+        }
+    }
+    Err(last_error.unwrap_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "could not resolve to any addresses",
+        )
+    }))
+}
+
+/// Get the lowest and highest height of a [`HeightMap`], or `None` if empty
+fn height_range(height_map: &HeightMap) -> Option<(i32, i32)> {
+    let mut heights = height_map.iter().map(|item| item.height());
+    let first = heights.next()?;
+    Some(heights.fold((first, first), |(low, high), height| {
+        (low.min(height), high.max(height))
+    }))
+}
+
+/// Error for a server response which could not be parsed
+fn malformed(response: &Response) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("malformed server response `{}`", response.as_str()),
+    )
+}
+
+/// Report a socket timeout as [`io::ErrorKind::TimedOut`], as some platforms
+/// report it as [`io::ErrorKind::WouldBlock`]
+pub(crate) fn timed_out(error: io::Error) -> io::Error {
+    match error.kind() {
+        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut => {
+            io::Error::new(io::ErrorKind::TimedOut, "timed out waiting for server")
+        }
+        _ => error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockConnection;
+
+    #[test]
+    fn carpet_region_clears_vegetation() {
+        let mut mc = MockConnection::new();
+        // Tall grass stands on the second column
+        mc.push_response("64,65");
+        mc.push_response("2,0;2,0;0,0;31,1");
+        mc.push_response("64,64");
+        mc.push_response("2,0;2,0");
+        mc.carpet_region([0, 0, 0], [1, 0, 0], Block::SNOW, 1, true)
+            .unwrap();
+        assert_eq!(mc.pending_responses(), 0);
+        let sent = mc.sent_commands();
+        assert!(sent.contains(&"world.setBlock(1,65,0,0,0)".to_string()));
+        assert_eq!(
+            sent[sent.len() - 2..],
+            ["world.setBlock(0,65,0,78,0)", "world.setBlock(1,65,0,78,0)"],
+        );
+    }
+
+    #[test]
+    fn drain_liquids_merges_runs_per_tile() {
+        let mut mc = MockConnection::new();
+        mc.enable_undo(100);
+        // Water everywhere except z = 3, across two tiles along z
+        let blocks = |range: std::ops::RangeInclusive<i32>| {
+            range
+                .map(|z| if z == 3 { "1,0" } else { "9,0" })
+                .collect::<Vec<_>>()
+                .join(";")
+        };
+        mc.push_response(blocks(0..=31));
+        mc.push_response(blocks(32..=39));
+        assert_eq!(mc.drain_liquids([0, 64, 0], [0, 64, 39]).unwrap(), 39);
+        assert_eq!(mc.pending_responses(), 0);
+        assert_eq!(
+            mc.sent_commands(),
+            [
+                "world.getBlocksWithData(0,64,0,0,64,31)",
+                "world.setBlocks(0,64,0,0,64,2,0,0)",
+                "world.setBlocks(0,64,4,0,64,31,0,0)",
+                "world.getBlocksWithData(0,64,32,0,64,39)",
+                "world.setBlocks(0,64,32,0,64,39,0,0)",
+            ],
+        );
+
+        // Undo restores from the tiles, without reading again
+        mc.clear_sent();
+        assert_eq!(mc.undo(3).unwrap(), 3);
+        let sent = mc.sent_commands();
+        assert_eq!(sent.len(), 39);
+        assert!(sent.iter().all(|command| command.ends_with(",9,0)")));
+    }
+
+    #[test]
+    fn build_path_places_stairs_on_slopes() {
+        let mut mc = MockConnection::new();
+        // The surface rises by one block between x = 1 and x = 2
+        let heights = (-1..=3)
+            .flat_map(|x| [if x < 2 { "64" } else { "65" }; 3])
+            .collect::<Vec<_>>();
+        mc.push_response(heights.join(","));
+        let waypoints = [Coordinate::new(0, 0, 0), Coordinate::new(2, 0, 0)];
+        mc.build_path(&waypoints, 1, Block::COBBLESTONE).unwrap();
+        let mut sent = mc.sent_commands();
+        sent.sort();
+        assert_eq!(
+            sent,
+            [
+                "world.getHeights(-1,-1,3,1)",
+                "world.setBlock(0,64,0,4,0)",
+                "world.setBlock(1,64,0,4,0)",
+                "world.setBlock(2,65,0,67,0)",
+            ],
+        );
+    }
+
+    #[test]
+    fn bridge_railings_stand_on_deck() {
+        let mut mc = MockConnection::new();
+        mc.push_response(["60"; 9].join(","));
+        let style = BridgeStyle {
+            width: 1,
+            pillar_spacing: 0,
+            ..BridgeStyle::default()
+        };
+        mc.build_bridge([0, 70, 0], [2, 70, 0], style).unwrap();
+        let sent = mc.sent_commands();
+        let place = |x: i32, y: i32, z: i32, block: Block| {
+            format!(
+                "world.setBlock({x},{y},{z},{},{})",
+                block.id, block.modifier
+            )
+        };
+        let railing = style.railing.unwrap();
+        for x in 0..=2 {
+            for z in [-1, 1] {
+                assert!(sent.contains(&place(x, 71, z, railing)));
+                assert!(sent.contains(&place(x, 70, z, style.deck)));
+            }
+        }
+    }
+
+    #[test]
+    fn command_block_is_recorded() {
+        let mut mc = MockConnection::new();
+        mc.enable_undo(10);
+        mc.start_edit_log();
+        mc.push_response("1,0");
+        mc.set_command_block([0, 64, 0], "say hi", CommandBlockMode::Impulse, true, false)
+            .unwrap();
+        assert_eq!(mc.edit_log().unwrap().len(), 1);
+        mc.clear_sent();
+        assert_eq!(mc.undo(1).unwrap(), 1);
+        assert_eq!(mc.sent_commands(), ["world.setBlock(0,64,0,1,0)"]);
+    }
+
+    #[test]
+    fn paced_writes_probe_once_per_interval() {
+        let mut mc = MockConnection::new();
+        mc.set_flow(Flow::Paced(3));
+        mc.push_response("64");
+        mc.push_response("64");
+        for x in 0..7 {
+            mc.set_block([x, 64, 0], Block::STONE).unwrap();
+        }
+        assert_eq!(mc.pending_responses(), 0);
+        let probes = mc
+            .sent_commands()
+            .iter()
+            .filter(|command| *command == "world.getHeight(0,0)")
+            .count();
+        assert_eq!(probes, 2);
+    }
 }
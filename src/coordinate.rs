@@ -1,21 +1,48 @@
-use std::{fmt, ops};
+use std::{error, fmt, ops, str::FromStr};
 
 use crate::chunk::Size;
 
 /// An absolute or relative coordinate in the Minecraft world
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct Coordinate {
     pub x: i32,
     pub y: i32,
     pub z: i32,
 }
 
+/// One of the three axes of the Minecraft world
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Axis {
+    /// East-west axis
+    X,
+    /// Vertical axis
+    Y,
+    /// North-south axis
+    Z,
+}
+
+/// Offsets of the 6 face-adjacent neighbors of a block
+pub(crate) const NEIGHBORS: [[i32; 3]; 6] = [
+    [1, 0, 0],
+    [-1, 0, 0],
+    [0, 1, 0],
+    [0, -1, 0],
+    [0, 0, 1],
+    [0, 0, -1],
+];
+
 impl Coordinate {
     /// Create a new coordinate
     pub const fn new(x: i32, y: i32, z: i32) -> Self {
         Self { x, y, z }
     }
 
+    /// Parse a coordinate from 3 integers separated by spaces and/or commas,
+    /// optionally in parentheses (eg. `"100 64 -20"` or `"(100, 64, -20)"`)
+    pub fn parse(string: &str) -> Result<Self, ParseCoordinateError> {
+        string.parse()
+    }
+
     // TODO(rename): Possibly a misleading method name?
     pub(crate) fn min(self, other: Self) -> Self {
         Coordinate {
@@ -25,6 +52,35 @@ impl Coordinate {
         }
     }
 
+    pub(crate) fn max(self, other: Self) -> Self {
+        Coordinate {
+            x: self.x.max(other.x),
+            y: self.y.max(other.y),
+            z: self.z.max(other.z),
+        }
+    }
+
+    /// Get the coordinates of a straight line from `self` to `other`
+    /// (inclusive), moving at most one block along each axis per step
+    pub(crate) fn line_to(self, other: Self) -> Vec<Self> {
+        let delta = other - self;
+        let steps = delta.x.abs().max(delta.y.abs()).max(delta.z.abs());
+        (0..=steps)
+            .map(|step| {
+                let progress = if steps == 0 {
+                    0.0
+                } else {
+                    step as f32 / steps as f32
+                };
+                self + Coordinate::new(
+                    (delta.x as f32 * progress).round() as i32,
+                    (delta.y as f32 * progress).round() as i32,
+                    (delta.z as f32 * progress).round() as i32,
+                )
+            })
+            .collect()
+    }
+
     pub(crate) fn size_between(self, other: Self) -> Size {
         Size {
             x: (self.x - other.x).unsigned_abs() + 1,
@@ -34,12 +90,61 @@ impl Coordinate {
     }
 }
 
+/// Error returned when parsing a [`Coordinate`] fails
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseCoordinateError {
+    /// The string which could not be parsed
+    pub input: String,
+}
+
 impl fmt::Display for Coordinate {
+    /// Formats as `(x, y, z)`, or as `x y z` like Minecraft commands with the
+    /// alternate flag (`{:#}`)
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "({}, {}, {})", self.x, self.y, self.z)
+        if f.alternate() {
+            write!(f, "{} {} {}", self.x, self.y, self.z)
+        } else {
+            write!(f, "({}, {}, {})", self.x, self.y, self.z)
+        }
+    }
+}
+
+impl FromStr for Coordinate {
+    type Err = ParseCoordinateError;
+
+    /// See [`Coordinate::parse`]
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        let error = || ParseCoordinateError {
+            input: string.to_string(),
+        };
+
+        let trimmed = string.trim();
+        let inner = trimmed
+            .strip_prefix('(')
+            .and_then(|inner| inner.strip_suffix(')'))
+            .unwrap_or(trimmed);
+        let mut values = inner
+            .split(|char: char| char == ',' || char.is_whitespace())
+            .filter(|value| !value.is_empty())
+            .map(|value| value.parse::<i32>().map_err(|_| error()));
+
+        let (Some(x), Some(y), Some(z), None) =
+            (values.next(), values.next(), values.next(), values.next())
+        else {
+            return Err(error());
+        };
+        Ok(Self::new(x?, y?, z?))
+    }
+}
+
+impl fmt::Display for ParseCoordinateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid coordinate `{}`", self.input)
     }
 }
 
+impl error::Error for ParseCoordinateError {}
+
 impl<T> ops::Add<T> for Coordinate
 where
     T: Into<Coordinate>,
@@ -0,0 +1,58 @@
+use crate::{Block, Coordinate};
+
+/// A record of block writes made through a [`Connection`]
+///
+/// Start recording with [`Connection::start_edit_log`]
+///
+/// [`Connection`]: crate::Connection
+/// [`Connection::start_edit_log`]: crate::Connection::start_edit_log
+#[derive(Clone, Debug, Default)]
+pub struct EditLog {
+    edits: Vec<Edit>,
+}
+
+/// A cuboid of blocks which were all set to the same [`Block`]
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Edit {
+    pub min: Coordinate,
+    pub max: Coordinate,
+    pub block: Block,
+}
+
+impl EditLog {
+    /// Create an empty edit log
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a cuboid specified by [`Coordinate`]s `a` and `b` (in any order)
+    /// being set to a [`Block`]
+    pub fn push(&mut self, a: impl Into<Coordinate>, b: impl Into<Coordinate>, block: Block) {
+        let a = a.into();
+        let b = b.into();
+        self.edits.push(Edit {
+            min: a.min(b),
+            max: a.max(b),
+            block,
+        });
+    }
+
+    /// Get the amount of recorded edits
+    pub fn len(&self) -> usize {
+        self.edits.len()
+    }
+
+    /// Returns `true` if no edits have been recorded
+    pub fn is_empty(&self) -> bool {
+        self.edits.is_empty()
+    }
+
+    /// Remove all recorded edits
+    pub fn clear(&mut self) {
+        self.edits.clear();
+    }
+
+    pub(crate) fn edits(&self) -> &[Edit] {
+        &self.edits
+    }
+}
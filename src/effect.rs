@@ -0,0 +1,81 @@
+/// A vanilla status effect, applied with [`Connection::give_effect`]
+///
+/// [`Connection::give_effect`]: crate::Connection::give_effect
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Effect {
+    Speed,
+    Slowness,
+    Haste,
+    MiningFatigue,
+    Strength,
+    InstantHealth,
+    InstantDamage,
+    JumpBoost,
+    Nausea,
+    Regeneration,
+    Resistance,
+    FireResistance,
+    WaterBreathing,
+    Invisibility,
+    Blindness,
+    NightVision,
+    Hunger,
+    Weakness,
+    Poison,
+    Wither,
+    HealthBoost,
+    Absorption,
+    Saturation,
+    Glowing,
+    Levitation,
+    Luck,
+    Unluck,
+    SlowFalling,
+    ConduitPower,
+    DolphinsGrace,
+    BadOmen,
+    HeroOfTheVillage,
+    Darkness,
+}
+
+impl Effect {
+    /// Get the namespaced identifier used by in-game commands, such as
+    /// `minecraft:speed`
+    pub const fn id(&self) -> &'static str {
+        match self {
+            Self::Speed => "minecraft:speed",
+            Self::Slowness => "minecraft:slowness",
+            Self::Haste => "minecraft:haste",
+            Self::MiningFatigue => "minecraft:mining_fatigue",
+            Self::Strength => "minecraft:strength",
+            Self::InstantHealth => "minecraft:instant_health",
+            Self::InstantDamage => "minecraft:instant_damage",
+            Self::JumpBoost => "minecraft:jump_boost",
+            Self::Nausea => "minecraft:nausea",
+            Self::Regeneration => "minecraft:regeneration",
+            Self::Resistance => "minecraft:resistance",
+            Self::FireResistance => "minecraft:fire_resistance",
+            Self::WaterBreathing => "minecraft:water_breathing",
+            Self::Invisibility => "minecraft:invisibility",
+            Self::Blindness => "minecraft:blindness",
+            Self::NightVision => "minecraft:night_vision",
+            Self::Hunger => "minecraft:hunger",
+            Self::Weakness => "minecraft:weakness",
+            Self::Poison => "minecraft:poison",
+            Self::Wither => "minecraft:wither",
+            Self::HealthBoost => "minecraft:health_boost",
+            Self::Absorption => "minecraft:absorption",
+            Self::Saturation => "minecraft:saturation",
+            Self::Glowing => "minecraft:glowing",
+            Self::Levitation => "minecraft:levitation",
+            Self::Luck => "minecraft:luck",
+            Self::Unluck => "minecraft:unluck",
+            Self::SlowFalling => "minecraft:slow_falling",
+            Self::ConduitPower => "minecraft:conduit_power",
+            Self::DolphinsGrace => "minecraft:dolphins_grace",
+            Self::BadOmen => "minecraft:bad_omen",
+            Self::HeroOfTheVillage => "minecraft:hero_of_the_village",
+            Self::Darkness => "minecraft:darkness",
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use std::{
+    fmt,
+    io::{self, BufRead, BufReader, Write},
+    net::TcpStream,
+    time::Duration,
+};
+
+use crate::{
+    command::{Command, LineEnding},
+    connection::{connect_timeout, timed_out},
+};
+
+/// Longest time connecting to an address, or answering its health check, may
+/// take
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Callback notified with the new address and the error which caused a
+/// failover
+type Callback = Box<dyn FnMut(&str, &io::Error) + Send>;
+
+/// Server addresses a [`Connection`] can fail over between
+///
+/// [`Connection`]: crate::Connection
+pub(crate) struct Failover {
+    addresses: Vec<String>,
+    current: usize,
+    pub read_timeout: Option<Duration>,
+    pub write_timeout: Option<Duration>,
+    pub nodelay: bool,
+    callback: Option<Callback>,
+}
+
+impl Failover {
+    /// Connect to the first address which passes a health check
+    ///
+    /// Returns the error of the last address if none pass
+    pub fn connect(
+        addresses: Vec<String>,
+        line_ending: LineEnding,
+    ) -> io::Result<(Self, TcpStream)> {
+        let mut failover = Self {
+            addresses,
+            current: 0,
+            read_timeout: None,
+            write_timeout: None,
+            nodelay: false,
+            callback: None,
+        };
+        let mut last_error = None;
+        for index in 0..failover.addresses.len() {
+            match failover.connect_to(index, line_ending) {
+                Ok(stream) => {
+                    failover.current = index;
+                    return Ok((failover, stream));
+                }
+                Err(error) => last_error = Some(error),
+            }
+        }
+        Err(last_error.unwrap_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "no server addresses given")
+        }))
+    }
+
+    /// Returns the address currently connected to
+    pub fn current_address(&self) -> &str {
+        &self.addresses[self.current]
+    }
+
+    pub fn set_callback(&mut self, callback: Callback) {
+        self.callback = Some(callback);
+    }
+
+    /// Connect to the next address which passes a health check, trying the
+    /// current address last, and notify the callback
+    ///
+    /// Returns `None` if every address fails
+    pub fn reconnect(&mut self, error: &io::Error, line_ending: LineEnding) -> Option<TcpStream> {
+        let count = self.addresses.len();
+        for offset in 1..=count {
+            let index = (self.current + offset) % count;
+            let Ok(stream) = self.connect_to(index, line_ending) else {
+                continue;
+            };
+            self.current = index;
+            if let Some(callback) = &mut self.callback {
+                callback(&self.addresses[index], error);
+            }
+            return Some(stream);
+        }
+        None
+    }
+
+    /// Connect to an address and check that the server answers a cheap query,
+    /// within [`HEALTH_CHECK_TIMEOUT`]
+    fn connect_to(&self, index: usize, line_ending: LineEnding) -> io::Result<TcpStream> {
+        let mut stream = connect_timeout(self.addresses[index].as_str(), HEALTH_CHECK_TIMEOUT)?;
+        stream.set_read_timeout(Some(HEALTH_CHECK_TIMEOUT))?;
+        stream.set_write_timeout(Some(HEALTH_CHECK_TIMEOUT))?;
+        stream.set_nodelay(self.nodelay)?;
+
+        let query = Command::new("world.getHeight").arg_int(0).arg_int(0);
+        stream
+            .write_all(query.build(line_ending).as_bytes())
+            .map_err(timed_out)?;
+        let mut response = String::new();
+        let read = BufReader::new(&stream)
+            .read_line(&mut response)
+            .map_err(timed_out)?;
+        if read == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "server closed the connection during health check",
+            ));
+        }
+        stream.set_read_timeout(self.read_timeout)?;
+        stream.set_write_timeout(self.write_timeout)?;
+        Ok(stream)
+    }
+}
+
+impl fmt::Debug for Failover {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Failover")
+            .field("addresses", &self.addresses)
+            .field("current", &self.current)
+            .field("read_timeout", &self.read_timeout)
+            .field("write_timeout", &self.write_timeout)
+            .field("nodelay", &self.nodelay)
+            .finish_non_exhaustive()
+    }
+}
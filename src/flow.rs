@@ -0,0 +1,17 @@
+/// How block writes made through a [`Connection`] are paced against the
+/// server
+///
+/// [`Connection`]: crate::Connection
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Flow {
+    /// Writes are sent as fast as possible (default)
+    #[default]
+    Unpaced,
+    /// After every `n` writes, wait for the server to answer a cheap query,
+    /// so it has processed every write sent before continuing
+    ///
+    /// Avoids overrunning the command queue of slower servers during large
+    /// pastes, at the cost of one round-trip per `n` writes. `Paced(0)` waits
+    /// after every write, the same as `Paced(1)`
+    Paced(u32),
+}
@@ -0,0 +1,153 @@
+use std::io;
+
+use crate::{chunk::Size, Block, Connection, Coordinate};
+
+/// A double-buffered cuboid of blocks for animation, which only sends the
+/// blocks that changed since the last frame
+///
+/// Draw the next frame with [`FrameBuffer::set`] or [`FrameBuffer::clear`],
+/// then send it with [`FrameBuffer::present`]
+///
+/// ```no_run
+/// # use mcrs::{Block, Connection, Coordinate, FrameBuffer};
+/// let mut mc = Connection::new().unwrap();
+/// let mut frame = FrameBuffer::new([0, 64, 0], [15, 64, 15]);
+/// for x in 0..16 {
+///     frame.clear(Block::AIR);
+///     frame.set(Coordinate::new(x, 64, 8), Block::GOLD_BLOCK);
+///     frame.present(&mut mc).unwrap();
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct FrameBuffer {
+    origin: Coordinate,
+    size: Size,
+    /// Blocks last sent to the server, or `None` if unknown
+    front: Option<Vec<Block>>,
+    back: Vec<Block>,
+}
+
+impl FrameBuffer {
+    /// Create a frame buffer for the cuboid specified by [`Coordinate`]s `a`
+    /// and `b` (in any order), with every block of the next frame set to air
+    ///
+    /// The current contents of the world are unknown, so the first
+    /// [`FrameBuffer::present`] sends every block. Use
+    /// [`FrameBuffer::capture`] to avoid this
+    pub fn new(a: impl Into<Coordinate>, b: impl Into<Coordinate>) -> Self {
+        let a = a.into();
+        let b = b.into();
+        let size = a.size_between(b);
+        Self {
+            origin: a.min(b),
+            size,
+            front: None,
+            back: vec![Block::AIR; size.volume()],
+        }
+    }
+
+    /// Read the current contents of the world as the last sent frame, and as
+    /// the starting point of the next frame
+    pub fn capture(&mut self, mc: &mut Connection) -> io::Result<()> {
+        let max = self.origin
+            + [
+                self.size.x as i32 - 1,
+                self.size.y as i32 - 1,
+                self.size.z as i32 - 1,
+            ];
+        let chunk = mc.get_blocks(self.origin, max)?;
+        let blocks: Vec<Block> = chunk.iter().map(|item| item.block()).collect();
+        self.back.clone_from(&blocks);
+        self.front = Some(blocks);
+        Ok(())
+    }
+
+    /// Get the origin [`Coordinate`]
+    pub fn origin(&self) -> Coordinate {
+        self.origin
+    }
+
+    /// Get the 3D size of the frame
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// Get the [`Block`] at the **absolute** [`Coordinate`] in the next frame
+    pub fn get(&self, coordinate: impl Into<Coordinate>) -> Option<Block> {
+        let index = self.index(coordinate.into())?;
+        Some(self.back[index])
+    }
+
+    /// Set the [`Block`] at the **absolute** [`Coordinate`] in the next frame
+    ///
+    /// Returns `false` if the coordinate is outside of the frame
+    pub fn set(&mut self, coordinate: impl Into<Coordinate>, block: Block) -> bool {
+        let Some(index) = self.index(coordinate.into()) else {
+            return false;
+        };
+        self.back[index] = block;
+        true
+    }
+
+    /// Set every block of the next frame
+    pub fn clear(&mut self, block: Block) {
+        self.back.fill(block);
+    }
+
+    /// Send every block of the next frame which differs from the last sent
+    /// frame, then start the following frame as a copy of it
+    ///
+    /// Consecutive changed blocks along the `z`-axis with the same [`Block`]
+    /// are sent together. Returns the amount of blocks changed
+    pub fn present(&mut self, mc: &mut Connection) -> io::Result<usize> {
+        let mut changed = 0;
+        let mut run: Option<(Coordinate, Coordinate, Block)> = None;
+        for (index, block) in self.back.iter().enumerate() {
+            let is_changed = match &self.front {
+                Some(front) => front[index] != *block,
+                None => true,
+            };
+            if !is_changed {
+                continue;
+            }
+            changed += 1;
+
+            let coordinate = self.size.index_to_coordinate(index) + self.origin;
+            if let Some((start, end, run_block)) = &mut run {
+                let continues = *run_block == *block
+                    && end.x == coordinate.x
+                    && end.y == coordinate.y
+                    && end.z + 1 == coordinate.z;
+                if continues {
+                    *end = coordinate;
+                    continue;
+                }
+                mc.set_blocks(*start, *end, *run_block)?;
+            }
+            run = Some((coordinate, coordinate, *block));
+        }
+        if let Some((start, end, block)) = run {
+            mc.set_blocks(start, end, block)?;
+        }
+
+        match &mut self.front {
+            Some(front) => front.clone_from(&self.back),
+            None => self.front = Some(self.back.clone()),
+        }
+        Ok(changed)
+    }
+
+    /// Forget the last sent frame, so the next [`FrameBuffer::present`] sends
+    /// every block
+    pub fn invalidate(&mut self) {
+        self.front = None;
+    }
+
+    fn index(&self, coordinate: Coordinate) -> Option<usize> {
+        let relative = coordinate - self.origin;
+        if !self.size.contains(relative) {
+            return None;
+        }
+        Some(self.size.coordinate_to_index(relative))
+    }
+}
@@ -0,0 +1,127 @@
+use std::ops;
+
+use crate::{block_state::Facing, Coordinate};
+
+/// A direction to look in, as Minecraft yaw and pitch in degrees
+///
+/// Yaw follows the Minecraft convention: `0` faces south (positive `z`), `90`
+/// faces west (negative `x`), `180` faces north and `-90` faces east. Pitch
+/// is `-90` looking straight up and `90` looking straight down
+///
+/// ```
+/// # use mcrs::{block_state::Facing, Heading};
+/// let heading = Heading::new(90.0, 0.0);
+/// assert_eq!(heading.facing(), Facing::West);
+/// let [x, _, z] = heading.to_vector();
+/// assert!((x + 1.0).abs() < 1e-6 && z.abs() < 1e-6);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Heading {
+    pub yaw: f32,
+    pub pitch: f32,
+}
+
+impl Heading {
+    /// Create a new heading
+    pub const fn new(yaw: f32, pitch: f32) -> Self {
+        Self { yaw, pitch }
+    }
+
+    /// Create a heading pointing along a vector, which does not need to be of
+    /// unit length
+    ///
+    /// A vertical vector has a yaw of `0`. A zero vector gives the default
+    /// heading
+    pub fn from_vector(vector: [f32; 3]) -> Self {
+        let [x, y, z] = vector;
+        let horizontal = x.hypot(z);
+        if horizontal == 0.0 && y == 0.0 {
+            return Self::default();
+        }
+        Self {
+            yaw: if horizontal == 0.0 {
+                0.0
+            } else {
+                (-x).atan2(z).to_degrees()
+            },
+            pitch: (-y).atan2(horizontal).to_degrees(),
+        }
+    }
+
+    /// Get the unit vector pointing along the heading, as `[x, y, z]`
+    pub fn to_vector(self) -> [f32; 3] {
+        let (yaw_sin, yaw_cos) = self.yaw.to_radians().sin_cos();
+        let (pitch_sin, pitch_cos) = self.pitch.to_radians().sin_cos();
+        [-yaw_sin * pitch_cos, -pitch_sin, yaw_cos * pitch_cos]
+    }
+
+    /// Get the horizontal [`Facing`] closest to the yaw
+    pub fn facing(self) -> Facing {
+        match (self.yaw / 90.0).round().rem_euclid(4.0) as u8 {
+            0 => Facing::South,
+            1 => Facing::West,
+            2 => Facing::North,
+            _ => Facing::East,
+        }
+    }
+
+    /// Get the offset of the adjacent block closest to the heading, including
+    /// straight up or down
+    pub fn to_offset(self) -> Coordinate {
+        let [x, y, z] = self.to_vector();
+        if y.abs() > x.abs() && y.abs() > z.abs() {
+            return Coordinate::new(0, y.signum() as i32, 0);
+        }
+        Coordinate::from(self.facing())
+    }
+
+    /// Get the equivalent heading with yaw from `-180` (inclusive) to `180`
+    /// (exclusive), and pitch clamped from `-90` to `90`
+    pub fn normalized(self) -> Self {
+        Self {
+            yaw: (self.yaw + 180.0).rem_euclid(360.0) - 180.0,
+            pitch: self.pitch.clamp(-90.0, 90.0),
+        }
+    }
+
+    /// Turn by another heading, adding its yaw and pitch and normalizing the
+    /// result
+    pub fn rotate(self, by: Heading) -> Self {
+        (self + by).normalized()
+    }
+}
+
+impl From<Facing> for Heading {
+    fn from(facing: Facing) -> Self {
+        let yaw = match facing {
+            Facing::South => 0.0,
+            Facing::West => 90.0,
+            Facing::North => 180.0,
+            Facing::East => -90.0,
+        };
+        Self::new(yaw, 0.0)
+    }
+}
+
+impl ops::Add for Heading {
+    type Output = Self;
+
+    /// Add the yaw and pitch, without normalizing. See [`Heading::rotate`]
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            yaw: self.yaw + rhs.yaw,
+            pitch: self.pitch + rhs.pitch,
+        }
+    }
+}
+
+impl ops::Sub for Heading {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self {
+            yaw: self.yaw - rhs.yaw,
+            pitch: self.pitch - rhs.pitch,
+        }
+    }
+}
@@ -1,6 +1,10 @@
-use std::{cmp::Ordering, fmt};
+use std::{
+    cmp::Ordering,
+    fmt,
+    io::{self, Write},
+};
 
-use crate::{chunk, Coordinate};
+use crate::{chunk, Block, Coordinate, EditLog};
 
 /// Stores a 2D area of the world with the `y`-values of the highest solid block
 /// at each (`x`, `z`)
@@ -54,9 +58,88 @@ impl HeightMap {
     }
 
     /// Create an iterator over the height values in the height map
-    pub fn iter(&self) -> Iter {
+    pub fn iter(&self) -> Iter<'_> {
         Iter::from(self)
     }
+
+    /// Render the height map as text, with one row per `z`-value (north at the
+    /// top) and one character per column
+    ///
+    /// Heights are scaled between the lowest and highest in the height map,
+    /// using the characters of `charset` from lowest to highest. An empty
+    /// `charset` uses [`HeightMap::DEFAULT_CHARSET`]
+    pub fn ascii_art(&self, charset: &str) -> String {
+        let mut charset: Vec<char> = charset.chars().collect();
+        if charset.is_empty() {
+            charset = Self::DEFAULT_CHARSET.chars().collect();
+        }
+        let min = self.list.iter().copied().min().unwrap_or(0);
+        let max = self.list.iter().copied().max().unwrap_or(0);
+        let range = (max - min).max(1) as f64;
+
+        let mut output = String::with_capacity((self.size.x as usize + 1) * self.size.z as usize);
+        for z in 0..self.size.z as i32 {
+            for x in 0..self.size.x as i32 {
+                let height = self.list[self.size.coordinate_to_index([x, 0, z])];
+                let level = (height - min) as f64 / range * (charset.len() - 1) as f64;
+                output.push(charset[level.round() as usize]);
+            }
+            output.push('\n');
+        }
+        output
+    }
+
+    /// Characters used by [`HeightMap::ascii_art`] and [`fmt::Display`], from
+    /// lowest to highest
+    pub const DEFAULT_CHARSET: &'static str = " .:-=+*#%@";
+
+    /// Write every height value as CSV rows of `x,z,height`, with **absolute**
+    /// coordinates and a header row
+    pub fn write_csv(&self, mut writer: impl Write) -> io::Result<()> {
+        writeln!(writer, "x,z,height")?;
+        for item in self.iter() {
+            let position = item.position_absolute();
+            writeln!(writer, "{},{},{}", position.x, position.z, item.height())?;
+        }
+        Ok(())
+    }
+
+    /// Update the height values locally to reflect the block writes recorded
+    /// in an [`EditLog`], without querying the server
+    ///
+    /// Removing the highest block of a column makes its new height unknown, so
+    /// the **absolute** `y`-agnostic [`Coordinate`]s of those columns are
+    /// returned to be re-fetched. Their height values are left as an upper
+    /// bound
+    pub fn apply_edits(&mut self, log: &EditLog) -> Vec<Coordinate> {
+        let mut stale = vec![false; self.list.len()];
+
+        for edit in log.edits() {
+            for x in edit.min.x..=edit.max.x {
+                for z in edit.min.z..=edit.max.z {
+                    let relative = Coordinate::new(x - self.origin.x, 0, z - self.origin.z);
+                    if !self.size.contains(relative) {
+                        continue;
+                    }
+                    let index = self.size.coordinate_to_index(relative);
+                    let height = &mut self.list[index];
+                    if edit.block != Block::AIR {
+                        if edit.max.y >= *height {
+                            *height = edit.max.y;
+                            stale[index] = false;
+                        }
+                    } else if (edit.min.y..=edit.max.y).contains(height) {
+                        stale[index] = true;
+                    }
+                }
+            }
+        }
+
+        (0..self.list.len())
+            .filter(|index| stale[*index])
+            .map(|index| self.size.index_to_coordinate(index) + self.origin)
+            .collect()
+    }
 }
 
 impl Size {
@@ -90,6 +173,14 @@ impl Size {
     }
 }
 
+impl fmt::Display for HeightMap {
+    /// Render the height map with [`HeightMap::ascii_art`] and the default
+    /// charset
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.ascii_art(Self::DEFAULT_CHARSET))
+    }
+}
+
 impl fmt::Debug for HeightMap {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "<HeightMap {}x{}>", self.size.x, self.size.z)
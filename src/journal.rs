@@ -0,0 +1,252 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, Read, Write},
+    path::Path,
+};
+
+use crate::{undo::Entry, Block, Chunk, Coordinate};
+
+/// Append-only file of block writes with their prior state, used to restore
+/// the world after a crash
+///
+/// Each record is its payload length and CRC-32 (both little-endian `u32`s),
+/// followed by the payload: the cuboid origin and size, the written block, and
+/// every prior block
+#[derive(Debug)]
+pub(crate) struct Journal {
+    file: File,
+}
+
+impl Journal {
+    /// Open a journal file for appending, creating it if it doesn't exist
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self { file })
+    }
+
+    /// Append an entry and wait for it to reach the disk
+    pub fn append(&mut self, entry: &Entry) -> io::Result<()> {
+        self.file.write_all(&encode_record(entry))?;
+        self.file.sync_data()
+    }
+
+    /// Read every intact entry of a journal file, oldest first
+    ///
+    /// Reading stops at the first truncated or corrupted record, which is
+    /// expected if the writer crashed while appending it
+    pub fn read(path: impl AsRef<Path>) -> io::Result<Vec<Entry>> {
+        let mut bytes = Vec::new();
+        File::open(path)?.read_to_end(&mut bytes)?;
+
+        let mut entries = Vec::new();
+        let mut rest = bytes.as_slice();
+        while let Some((entry, remaining)) = parse_record(rest) {
+            entries.push(entry);
+            rest = remaining;
+        }
+        Ok(entries)
+    }
+}
+
+/// Encode an entry as a record, with its length and checksum
+fn encode_record(entry: &Entry) -> Vec<u8> {
+    let origin = entry.before.origin();
+    let size = entry.before.size();
+    let mut payload = Vec::new();
+    for value in [origin.x, origin.y, origin.z] {
+        payload.extend_from_slice(&value.to_le_bytes());
+    }
+    for value in [size.x, size.y, size.z] {
+        payload.extend_from_slice(&value.to_le_bytes());
+    }
+    for block in [entry.block]
+        .into_iter()
+        .chain(entry.before.iter().map(|item| item.block()))
+    {
+        payload.extend_from_slice(&block.id.to_le_bytes());
+        payload.extend_from_slice(&block.modifier.to_le_bytes());
+    }
+
+    let mut record = Vec::with_capacity(payload.len() + 8);
+    record.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    record.extend_from_slice(&crc32(&payload).to_le_bytes());
+    record.extend_from_slice(&payload);
+    record
+}
+
+/// Parse one record, returning the entry and the bytes after it
+fn parse_record(bytes: &[u8]) -> Option<(Entry, &[u8])> {
+    let length = read_u32(bytes, 0)? as usize;
+    let checksum = read_u32(bytes, 4)?;
+    let payload = bytes.get(8..8 + length)?;
+    if crc32(payload) != checksum {
+        return None;
+    }
+
+    let int = |index: usize| read_u32(payload, index * 4).map(|value| value as i32);
+    let origin = Coordinate::new(int(0)?, int(1)?, int(2)?);
+    let [x, y, z] = [int(3)?, int(4)?, int(5)?];
+    if x < 1 || y < 1 || z < 1 {
+        return None;
+    }
+    let block = Block::new(int(6)?, int(7)?);
+    let volume = (x as usize)
+        .checked_mul(y as usize)?
+        .checked_mul(z as usize)?;
+    if Some(payload.len()) != volume.checked_mul(2).and_then(|n| (n + 8).checked_mul(4)) {
+        return None;
+    }
+    let list = (0..volume)
+        .map(|i| Some(Block::new(int(8 + i * 2)?, int(9 + i * 2)?)))
+        .collect::<Option<Vec<_>>>()?;
+
+    let far = Coordinate::new(
+        origin.x.checked_add(x - 1)?,
+        origin.y.checked_add(y - 1)?,
+        origin.z.checked_add(z - 1)?,
+    );
+    let before = Chunk::new(origin, far, list);
+    Some((Entry { before, block }, &bytes[8 + length..]))
+}
+
+fn read_u32(bytes: &[u8], index: usize) -> Option<u32> {
+    let slice = bytes.get(index..index + 4)?;
+    Some(u32::from_le_bytes(slice.try_into().ok()?))
+}
+
+/// CRC-32 (IEEE) checksum
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in bytes {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xedb8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(size: [i32; 3]) -> Entry {
+        let [x, y, z] = size;
+        let volume = (x * y * z) as usize;
+        let list = (0..volume as i32).map(|i| Block::new(i, i % 16)).collect();
+        let origin = Coordinate::new(-4, 60, 7);
+        Entry {
+            before: Chunk::new(origin, origin + [x - 1, y - 1, z - 1], list),
+            block: Block::GLASS,
+        }
+    }
+
+    /// Build a record around a raw payload, with a valid checksum
+    fn frame(payload: &[u8]) -> Vec<u8> {
+        let mut record = (payload.len() as u32).to_le_bytes().to_vec();
+        record.extend_from_slice(&crc32(payload).to_le_bytes());
+        record.extend_from_slice(payload);
+        record
+    }
+
+    /// Build a payload of a size and a written block followed by
+    /// `block_count` prior blocks
+    fn payload(size: [i32; 3], block_count: usize) -> Vec<u8> {
+        let mut payload = Vec::new();
+        for value in [0, 0, 0].into_iter().chain(size) {
+            payload.extend_from_slice(&value.to_le_bytes());
+        }
+        payload.resize(payload.len() + (block_count + 1) * 8, 0);
+        payload
+    }
+
+    fn assert_same(a: &Entry, b: &Entry) {
+        assert_eq!(a.block, b.block);
+        assert_eq!(a.before.origin(), b.before.origin());
+        let (a_size, b_size) = (a.before.size(), b.before.size());
+        assert_eq!(
+            (a_size.x, a_size.y, a_size.z),
+            (b_size.x, b_size.y, b_size.z)
+        );
+        let a_blocks: Vec<_> = a.before.iter().map(|item| item.block()).collect();
+        let b_blocks: Vec<_> = b.before.iter().map(|item| item.block()).collect();
+        assert_eq!(a_blocks, b_blocks);
+    }
+
+    #[test]
+    fn crc32_matches_reference() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+    }
+
+    #[test]
+    fn round_trip() {
+        for size in [[1, 1, 1], [3, 5, 7], [2, 1, 6]] {
+            let original = entry(size);
+            let record = encode_record(&original);
+            let (parsed, rest) = parse_record(&record).unwrap();
+            assert_same(&original, &parsed);
+            assert!(rest.is_empty());
+        }
+    }
+
+    #[test]
+    fn consecutive_records() {
+        let first = entry([1, 1, 1]);
+        let second = entry([2, 3, 1]);
+        let mut bytes = encode_record(&first);
+        bytes.extend(encode_record(&second));
+
+        let (parsed, rest) = parse_record(&bytes).unwrap();
+        assert_same(&first, &parsed);
+        let (parsed, rest) = parse_record(rest).unwrap();
+        assert_same(&second, &parsed);
+        assert!(parse_record(rest).is_none());
+    }
+
+    #[test]
+    fn empty_input() {
+        assert!(parse_record(&[]).is_none());
+    }
+
+    #[test]
+    fn truncated_record() {
+        let record = encode_record(&entry([2, 2, 3]));
+        for length in 0..record.len() {
+            assert!(parse_record(&record[..length]).is_none(), "{length}");
+        }
+    }
+
+    #[test]
+    fn corrupted_record() {
+        let record = encode_record(&entry([2, 2, 2]));
+        for index in 0..record.len() {
+            let mut corrupted = record.clone();
+            corrupted[index] ^= 0x10;
+            assert!(parse_record(&corrupted).is_none(), "{index}");
+        }
+    }
+
+    #[test]
+    fn volume_must_match_payload() {
+        assert!(parse_record(&frame(&payload([2, 2, 2], 8))).is_some());
+        assert!(parse_record(&frame(&payload([2, 2, 2], 7))).is_none());
+        assert!(parse_record(&frame(&payload([2, 2, 2], 9))).is_none());
+    }
+
+    #[test]
+    fn invalid_size() {
+        assert!(parse_record(&frame(&payload([0, 1, 1], 0))).is_none());
+        assert!(parse_record(&frame(&payload([1, 0, 1], 0))).is_none());
+        assert!(parse_record(&frame(&payload([-1, 1, 1], 0))).is_none());
+        assert!(parse_record(&frame(&payload([i32::MAX, i32::MAX, i32::MAX], 0))).is_none());
+    }
+
+    #[test]
+    fn origin_overflow() {
+        let mut payload = payload([2, 1, 1], 2);
+        payload[..4].copy_from_slice(&i32::MAX.to_le_bytes());
+        assert!(parse_record(&frame(&payload)).is_none());
+    }
+}
@@ -4,25 +4,85 @@
 //!
 //! Requires a server running [ELCI](https://github.com/rozukke/elci).
 //!
-//! ```
+//! ```no_run
 //! # use mcrs::Connection;
 //! let mut mc = Connection::new().unwrap();
 //! mc.post_to_chat("Hello world!").unwrap();
 //! ```
 
+/// Typed decoding of [`Block`] modifiers
+pub mod block_state;
 /// Types related to [`Chunk`]
 pub mod chunk;
+/// Compatibility shims for porting code from other libraries
+pub mod compat;
 /// Types related to [`HeightMap`]
 pub mod height_map;
+/// Typed redstone components, which encode into [`Block`] modifiers
+pub mod redstone;
+/// Coordinate generators for 3D shapes
+pub mod shapes;
+/// Test doubles for code using a [`Connection`]
+pub mod testing;
 
+mod aliases;
+mod batch;
 mod block;
+mod bridge;
+mod cache;
+mod clipboard;
 mod command;
+mod command_block;
 mod connection;
 mod coordinate;
+mod edit_log;
+mod effect;
+mod failover;
+mod flow;
+mod frame_buffer;
+mod heading;
+mod journal;
+mod library;
+mod metrics;
+mod noise;
+mod pool;
+mod preview;
+mod profile;
+mod region;
 mod response;
+mod sparse_world;
+mod sync;
+mod timing;
+mod transport;
+mod undo;
+mod world_limits;
 
-pub use block::Block;
+pub use aliases::BlockAliases;
+pub use batch::Batch;
+pub use block::{Block, ParseBlockError};
+pub use bridge::BridgeStyle;
 pub use chunk::Chunk;
-pub use connection::Connection;
-pub use coordinate::Coordinate;
+pub use clipboard::Clipboard;
+pub use command::LineEnding;
+pub use command_block::CommandBlockMode;
+pub use connection::{Connection, ConnectionBuilder};
+pub use coordinate::{Axis, Coordinate, ParseCoordinateError};
+pub use edit_log::EditLog;
+pub use effect::Effect;
+pub use flow::Flow;
+pub use frame_buffer::FrameBuffer;
+pub use heading::Heading;
 pub use height_map::HeightMap;
+pub use library::{LibraryEntry, StructureLibrary};
+pub use metrics::{CommandMetrics, Metrics};
+pub use noise::NoiseParams;
+pub use pool::{ConnectionPool, PooledConnection};
+pub use preview::Preview;
+pub use profile::Profile;
+pub use region::{FillHeight, Polygon, Region, Region2D};
+pub use response::{ParseMode, Response};
+pub use sparse_world::SparseWorld;
+pub use sync::sync_region;
+pub use timing::Timing;
+pub use transport::Transport;
+pub use world_limits::{OutOfWorld, WorldLimits};
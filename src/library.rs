@@ -0,0 +1,338 @@
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::{chunk::Size, Chunk};
+
+/// Name of the index file within a library directory
+const INDEX_FILE: &str = "index.tsv";
+/// Name of the file the index is written to before replacing [`INDEX_FILE`]
+const TEMPORARY_INDEX_FILE: &str = "index.tsv.tmp";
+/// Characters which would break the tab-separated index
+const INVALID_CHARS: [char; 3] = ['\t', '\n', '\r'];
+
+/// A directory of saved [`Chunk`]s, catalogued by an index file
+///
+/// The index is read when the library is opened, so searching does not touch
+/// the chunk files. Chunks are only read from disk by [`StructureLibrary::load`]
+///
+/// Tags are read from the `"tags"` metadata entry of a chunk (see
+/// [`Chunk::metadata`]), as a comma-separated list
+///
+/// ```no_run
+/// # use mcrs::{Connection, StructureLibrary};
+/// let mut mc = Connection::new().unwrap();
+/// let mut library = StructureLibrary::open("structures").unwrap();
+///
+/// let mut house = mc.get_blocks([0, 64, 0], [8, 72, 8]).unwrap();
+/// house.set_metadata("tags", "house,oak");
+/// library.insert("small house", &house).unwrap();
+///
+/// for entry in library.with_tag("house") {
+///     println!("{}: {:?}", entry.name(), entry.size());
+/// }
+/// let house = library.load("small house").unwrap();
+/// mc.paste(&house, [20, 64, 0]).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct StructureLibrary {
+    directory: PathBuf,
+    entries: BTreeMap<String, LibraryEntry>,
+}
+
+/// Catalogue information for a [`Chunk`] in a [`StructureLibrary`]
+#[derive(Clone, Debug)]
+pub struct LibraryEntry {
+    name: String,
+    file: String,
+    size: Size,
+    tags: Vec<String>,
+}
+
+impl StructureLibrary {
+    /// Open the library in a directory, creating the directory if it does not
+    /// exist
+    pub fn open(directory: impl Into<PathBuf>) -> io::Result<Self> {
+        let directory = directory.into();
+        fs::create_dir_all(&directory)?;
+
+        let mut entries = BTreeMap::new();
+        let index = match fs::read_to_string(directory.join(INDEX_FILE)) {
+            Ok(index) => index,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(error) => return Err(error),
+        };
+        for line in index.lines().filter(|line| !line.is_empty()) {
+            let entry = LibraryEntry::parse(line).ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidData, "malformed library index")
+            })?;
+            entries.insert(entry.name.clone(), entry);
+        }
+
+        Ok(Self { directory, entries })
+    }
+
+    /// Get the directory of the library
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Save a [`Chunk`] under a name, replacing any chunk with the same name
+    ///
+    /// Names and tags cannot contain tabs or line breaks
+    pub fn insert(&mut self, name: impl Into<String>, chunk: &Chunk) -> io::Result<()> {
+        let name = name.into();
+        if name.is_empty() || name.contains(INVALID_CHARS) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid structure name",
+            ));
+        }
+        let tags: Vec<String> = chunk
+            .metadata("tags")
+            .map(|tags| parse_tags(tags).map(String::from).collect())
+            .unwrap_or_default();
+        if tags.iter().any(|tag| tag.contains(INVALID_CHARS)) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid structure tag",
+            ));
+        }
+
+        let file = match self.entries.get(&name) {
+            Some(entry) => entry.file.clone(),
+            None => self.unused_file_name(&name),
+        };
+        let mut writer = BufWriter::new(File::create(self.directory.join(&file))?);
+        chunk.write_to(&mut writer)?;
+        writer.flush()?;
+
+        let entry = LibraryEntry {
+            name: name.clone(),
+            file,
+            size: chunk.size(),
+            tags,
+        };
+        self.entries.insert(name, entry);
+        self.save_index()
+    }
+
+    /// Read the [`Chunk`] saved under a name from disk
+    ///
+    /// Fails with [`io::ErrorKind::NotFound`] if there is no such structure
+    pub fn load(&self, name: &str) -> io::Result<Chunk> {
+        let entry = self.entries.get(name).ok_or_else(not_found)?;
+        let reader = BufReader::new(File::open(self.directory.join(&entry.file))?);
+        Chunk::read_from(reader)
+    }
+
+    /// Delete the [`Chunk`] saved under a name
+    ///
+    /// Fails with [`io::ErrorKind::NotFound`] if there is no such structure
+    pub fn remove(&mut self, name: &str) -> io::Result<()> {
+        let entry = self.entries.remove(name).ok_or_else(not_found)?;
+        self.save_index()?;
+        match fs::remove_file(self.directory.join(&entry.file)) {
+            Err(error) if error.kind() != io::ErrorKind::NotFound => Err(error),
+            _ => Ok(()),
+        }
+    }
+
+    /// Get the catalogue information for a name, if saved
+    pub fn get(&self, name: &str) -> Option<&LibraryEntry> {
+        self.entries.get(name)
+    }
+
+    /// Create an iterator over every entry, sorted by name
+    pub fn entries(&self) -> impl Iterator<Item = &LibraryEntry> {
+        self.entries.values()
+    }
+
+    /// Create an iterator over every entry with a name containing `query`,
+    /// ignoring case
+    pub fn search<'a>(&'a self, query: &str) -> impl Iterator<Item = &'a LibraryEntry> {
+        let query = query.to_lowercase();
+        self.entries()
+            .filter(move |entry| entry.name.to_lowercase().contains(&query))
+    }
+
+    /// Create an iterator over every entry with a tag
+    pub fn with_tag<'a>(&'a self, tag: &'a str) -> impl Iterator<Item = &'a LibraryEntry> {
+        self.entries().filter(move |entry| entry.has_tag(tag))
+    }
+
+    /// Create an iterator over every entry which fits within a [`Size`]
+    pub fn fitting(&self, max: Size) -> impl Iterator<Item = &LibraryEntry> {
+        self.entries().filter(move |entry| {
+            entry.size.x <= max.x && entry.size.y <= max.y && entry.size.z <= max.z
+        })
+    }
+
+    /// Create a file name from a structure name, which is not used by any
+    /// other entry
+    fn unused_file_name(&self, name: &str) -> String {
+        let stem: String = name
+            .chars()
+            .map(|char| match char {
+                'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' => char,
+                _ => '_',
+            })
+            .collect();
+        // File systems on macOS and Windows ignore case by default
+        let is_used = |file: &str| {
+            self.entries
+                .values()
+                .any(|entry| entry.file.eq_ignore_ascii_case(file))
+        };
+        let mut file = format!("{}.chunk", stem);
+        let mut counter = 1;
+        while is_used(&file) {
+            counter += 1;
+            file = format!("{}-{}.chunk", stem, counter);
+        }
+        file
+    }
+
+    /// Write the index to a temporary file, then rename it over the index, so
+    /// a crash while writing cannot leave a partial index
+    fn save_index(&self) -> io::Result<()> {
+        let temporary = self.directory.join(TEMPORARY_INDEX_FILE);
+        let mut writer = BufWriter::new(File::create(&temporary)?);
+        for entry in self.entries.values() {
+            writeln!(
+                writer,
+                "{}\t{}\t{},{},{}\t{}",
+                entry.name,
+                entry.file,
+                entry.size.x,
+                entry.size.y,
+                entry.size.z,
+                entry.tags.join(","),
+            )?;
+        }
+        writer
+            .into_inner()
+            .map_err(|error| error.into_error())?
+            .sync_all()?;
+        fs::rename(temporary, self.directory.join(INDEX_FILE))
+    }
+}
+
+impl LibraryEntry {
+    /// Get the name the structure is saved under
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the 3D size of the structure
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// Get the tags of the structure
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    /// Returns `true` if the structure has a tag
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|other| other == tag)
+    }
+
+    /// Parse a line of the index file
+    fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.split('\t');
+        let name = fields.next()?.to_string();
+        let file = fields.next()?.to_string();
+        let mut size = fields.next()?.split(',').map(|value| value.parse().ok());
+        let size = Size {
+            x: size.next()??,
+            y: size.next()??,
+            z: size.next()??,
+        };
+        let tags = parse_tags(fields.next().unwrap_or_default())
+            .map(String::from)
+            .collect();
+        Some(Self {
+            name,
+            file,
+            size,
+            tags,
+        })
+    }
+}
+
+fn parse_tags(tags: &str) -> impl Iterator<Item = &str> {
+    tags.split(',').map(str::trim).filter(|tag| !tag.is_empty())
+}
+
+fn not_found() -> io::Error {
+    io::Error::new(io::ErrorKind::NotFound, "no such structure in library")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process};
+
+    use super::*;
+    use crate::Block;
+
+    fn directory(name: &str) -> PathBuf {
+        let directory = env::temp_dir().join(format!("mcrs-library-{}-{}", name, process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        directory
+    }
+
+    fn chunk(tags: &str) -> Chunk {
+        let mut chunk = Chunk::new([0, 0, 0], [1, 0, 2], vec![Block::STONE; 6]);
+        chunk.set_metadata("tags", tags);
+        chunk
+    }
+
+    #[test]
+    fn index_round_trip() {
+        let directory = directory("round-trip");
+        let mut library = StructureLibrary::open(&directory).unwrap();
+        library.insert("small house", &chunk("house, oak")).unwrap();
+        library.insert("tower", &chunk("")).unwrap();
+        assert!(!directory.join(TEMPORARY_INDEX_FILE).exists());
+
+        let library = StructureLibrary::open(&directory).unwrap();
+        let house = library.get("small house").unwrap();
+        assert_eq!(house.tags(), ["house", "oak"]);
+        assert_eq!(house.size().z, 3);
+        assert!(library.get("tower").unwrap().tags().is_empty());
+        assert_eq!(library.load("tower").unwrap().size().x, 2);
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn rejects_invalid_tags() {
+        let directory = directory("invalid-tags");
+        let mut library = StructureLibrary::open(&directory).unwrap();
+        for tags in ["a\tb", "house,line\nbreak", "carriage\rreturn"] {
+            let error = library.insert("house", &chunk(tags)).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        }
+        assert!(library.get("house").is_none());
+        assert_eq!(fs::read_dir(&directory).unwrap().count(), 0);
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn file_names_differ_ignoring_case() {
+        let directory = directory("case");
+        let mut library = StructureLibrary::open(&directory).unwrap();
+        library.insert("House", &chunk("")).unwrap();
+        library.insert("house", &chunk("")).unwrap();
+        let upper = &library.get("House").unwrap().file;
+        let lower = &library.get("house").unwrap().file;
+        assert!(!upper.eq_ignore_ascii_case(lower));
+        assert_eq!(library.load("House").unwrap().size().x, 2);
+        assert_eq!(library.load("house").unwrap().size().x, 2);
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -0,0 +1,203 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fmt::Write,
+    time::{Duration, Instant},
+};
+
+/// Maximum amount of latency samples kept for each command
+const LATENCY_SAMPLES: usize = 1024;
+
+/// Quantiles included in [`Metrics::to_prometheus`]
+const QUANTILES: [f64; 3] = [0.5, 0.9, 0.99];
+
+/// Statistics about the traffic of a [`Connection`]
+///
+/// [`Connection`]: crate::Connection
+#[derive(Clone, Debug, Default)]
+pub struct Metrics {
+    commands: BTreeMap<String, CommandMetrics>,
+    bytes_sent: u64,
+    bytes_received: u64,
+    /// Commands awaiting a response, oldest first, and when they were sent
+    pending: VecDeque<(String, Instant)>,
+}
+
+/// Statistics about a single command name, such as `world.setBlock`
+#[derive(Clone, Debug, Default)]
+pub struct CommandMetrics {
+    count: u64,
+    latencies: VecDeque<Duration>,
+}
+
+impl Metrics {
+    /// Get the total amount of bytes sent to the server
+    pub fn bytes_sent(&self) -> u64 {
+        self.bytes_sent
+    }
+
+    /// Get the total amount of bytes received from the server
+    pub fn bytes_received(&self) -> u64 {
+        self.bytes_received
+    }
+
+    /// Get the statistics for a command name, if it has been sent
+    pub fn command(&self, name: &str) -> Option<&CommandMetrics> {
+        self.commands.get(name)
+    }
+
+    /// Create an iterator over every command name sent and its statistics,
+    /// sorted by name
+    pub fn commands(&self) -> impl Iterator<Item = (&str, &CommandMetrics)> {
+        self.commands
+            .iter()
+            .map(|(name, metrics)| (name.as_str(), metrics))
+    }
+
+    /// Format the statistics in the Prometheus text exposition format
+    pub fn to_prometheus(&self) -> String {
+        let mut output = String::new();
+        // Writing to a `String` cannot fail
+        let _ = self.write_prometheus(&mut output);
+        output
+    }
+
+    fn write_prometheus(&self, output: &mut String) -> std::fmt::Result {
+        writeln!(
+            output,
+            "# HELP mcrs_bytes_sent_total Bytes sent to the server"
+        )?;
+        writeln!(output, "# TYPE mcrs_bytes_sent_total counter")?;
+        writeln!(output, "mcrs_bytes_sent_total {}", self.bytes_sent)?;
+        writeln!(
+            output,
+            "# HELP mcrs_bytes_received_total Bytes received from the server"
+        )?;
+        writeln!(output, "# TYPE mcrs_bytes_received_total counter")?;
+        writeln!(output, "mcrs_bytes_received_total {}", self.bytes_received)?;
+
+        writeln!(
+            output,
+            "# HELP mcrs_commands_total Commands sent to the server"
+        )?;
+        writeln!(output, "# TYPE mcrs_commands_total counter")?;
+        for (name, metrics) in &self.commands {
+            writeln!(
+                output,
+                "mcrs_commands_total{{command=\"{}\"}} {}",
+                name, metrics.count
+            )?;
+        }
+
+        writeln!(
+            output,
+            "# HELP mcrs_command_latency_seconds Time from sending a command to receiving its response"
+        )?;
+        writeln!(output, "# TYPE mcrs_command_latency_seconds summary")?;
+        for (name, metrics) in &self.commands {
+            if metrics.latencies.is_empty() {
+                continue;
+            }
+            for quantile in QUANTILES {
+                if let Some(latency) = metrics.latency_percentile(quantile) {
+                    writeln!(
+                        output,
+                        "mcrs_command_latency_seconds{{command=\"{}\",quantile=\"{}\"}} {}",
+                        name,
+                        quantile,
+                        latency.as_secs_f64()
+                    )?;
+                }
+            }
+            let sum: Duration = metrics.latencies.iter().sum();
+            writeln!(
+                output,
+                "mcrs_command_latency_seconds_sum{{command=\"{}\"}} {}",
+                name,
+                sum.as_secs_f64()
+            )?;
+            writeln!(
+                output,
+                "mcrs_command_latency_seconds_count{{command=\"{}\"}} {}",
+                name,
+                metrics.latencies.len()
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Record a command being sent
+    pub(crate) fn record_send(&mut self, name: &str, bytes: usize) {
+        self.bytes_sent += bytes as u64;
+        self.commands.entry(name.to_string()).or_default().count += 1;
+        if expects_response(name) {
+            self.pending.push_back((name.to_string(), Instant::now()));
+        }
+    }
+
+    /// Record a response being received, attributing its latency to the
+    /// oldest command awaiting a response
+    pub(crate) fn record_recv(&mut self, bytes: usize) {
+        self.bytes_received += bytes as u64;
+        let Some((name, sent)) = self.pending.pop_front() else {
+            return;
+        };
+        let latencies = &mut self.commands.entry(name).or_default().latencies;
+        if latencies.len() >= LATENCY_SAMPLES {
+            latencies.pop_front();
+        }
+        latencies.push_back(sent.elapsed());
+    }
+}
+
+impl CommandMetrics {
+    /// Get the amount of times the command was sent
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Get the latency below which `quantile` (from `0.0` to `1.0`) of
+    /// responses were received, if the command has received any response
+    ///
+    /// Only the most recent 1024 responses are considered
+    pub fn latency_percentile(&self, quantile: f64) -> Option<Duration> {
+        if self.latencies.is_empty() {
+            return None;
+        }
+        let mut sorted: Vec<Duration> = self.latencies.iter().copied().collect();
+        sorted.sort_unstable();
+        let rank = (quantile.clamp(0.0, 1.0) * sorted.len() as f64).ceil() as usize;
+        Some(sorted[rank.saturating_sub(1)])
+    }
+}
+
+/// Check whether the server responds to a command name, such as
+/// `world.getBlock` or `events.chat.posts`
+fn expects_response(name: &str) -> bool {
+    let method = name.rsplit('.').next().unwrap_or(name);
+    method.starts_with("get") || matches!(method, "hits" | "posts")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::MockConnection;
+
+    #[test]
+    fn pipelined_latencies_per_command() {
+        let mut mc = MockConnection::new();
+        mc.push_response("1,0");
+        mc.push_response("64");
+        mc.send_raw("world.getBlockWithData", ["0", "64", "0"])
+            .unwrap();
+        mc.send_raw("world.setBlock", ["0", "65", "0", "1", "0"])
+            .unwrap();
+        mc.send_raw("world.getHeight", ["0", "0"]).unwrap();
+        mc.recv_raw().unwrap();
+        mc.recv_raw().unwrap();
+
+        let metrics = mc.metrics();
+        let samples = |name| metrics.command(name).unwrap().latencies.len();
+        assert_eq!(samples("world.getBlockWithData"), 1);
+        assert_eq!(samples("world.getHeight"), 1);
+        assert_eq!(samples("world.setBlock"), 0);
+    }
+}
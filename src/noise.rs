@@ -0,0 +1,81 @@
+use crate::Coordinate;
+
+/// Parameters for seeded 3D value noise
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NoiseParams {
+    /// Seed for the noise, different seeds give unrelated noise
+    pub seed: u32,
+    /// Size of the largest noise features, in blocks
+    pub scale: f32,
+    /// Amount of layers of finer noise added on top of the base layer
+    pub octaves: u32,
+}
+
+impl Default for NoiseParams {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            scale: 16.0,
+            octaves: 3,
+        }
+    }
+}
+
+impl NoiseParams {
+    /// Sample the noise at a [`Coordinate`], giving a value between `0.0` and
+    /// `1.0`
+    pub fn sample(&self, coordinate: impl Into<Coordinate>) -> f32 {
+        let coordinate = coordinate.into();
+        let mut total = 0.0;
+        let mut amplitude = 1.0;
+        let mut max = 0.0;
+        let mut scale = self.scale.max(f32::EPSILON);
+        for octave in 0..=self.octaves {
+            let seed = self.seed.wrapping_add(octave);
+            total += amplitude * sample_layer(seed, coordinate, scale);
+            max += amplitude;
+            amplitude /= 2.0;
+            scale /= 2.0;
+        }
+        total / max
+    }
+}
+
+/// Sample one layer of trilinearly interpolated value noise
+fn sample_layer(seed: u32, coordinate: Coordinate, scale: f32) -> f32 {
+    let position = [
+        coordinate.x as f32 / scale,
+        coordinate.y as f32 / scale,
+        coordinate.z as f32 / scale,
+    ];
+    let cell = position.map(|value| value.floor());
+    let [tx, ty, tz] = [0, 1, 2].map(|i| smoothstep(position[i] - cell[i]));
+    let [x, y, z] = cell.map(|value| value as i32);
+
+    let corner = |dx, dy, dz| hash(seed, x + dx, y + dy, z + dz);
+    let lerp = |a: f32, b: f32, t: f32| a + (b - a) * t;
+
+    let x00 = lerp(corner(0, 0, 0), corner(1, 0, 0), tx);
+    let x10 = lerp(corner(0, 1, 0), corner(1, 1, 0), tx);
+    let x01 = lerp(corner(0, 0, 1), corner(1, 0, 1), tx);
+    let x11 = lerp(corner(0, 1, 1), corner(1, 1, 1), tx);
+    let y0 = lerp(x00, x10, ty);
+    let y1 = lerp(x01, x11, ty);
+    lerp(y0, y1, tz)
+}
+
+fn smoothstep(t: f32) -> f32 {
+    t * t * (3.0 - 2.0 * t)
+}
+
+/// Hash a lattice point to a value between `0.0` and `1.0`
+fn hash(seed: u32, x: i32, y: i32, z: i32) -> f32 {
+    let mut hash = seed
+        ^ (x as u32).wrapping_mul(0x27d4_eb2d)
+        ^ (y as u32).wrapping_mul(0x1656_67b1)
+        ^ (z as u32).wrapping_mul(0x9e37_79b9);
+    hash = (hash ^ (hash >> 15)).wrapping_mul(0x85eb_ca6b);
+    hash = (hash ^ (hash >> 13)).wrapping_mul(0xc2b2_ae35);
+    hash ^= hash >> 16;
+    hash as f32 / u32::MAX as f32
+}
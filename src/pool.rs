@@ -0,0 +1,290 @@
+use std::{
+    io,
+    ops::{Deref, DerefMut},
+    sync::{Condvar, Mutex, MutexGuard, PoisonError},
+    thread,
+};
+
+use crate::{Block, Chunk, Connection, ConnectionBuilder, Coordinate};
+
+/// A fixed set of [`Connection`]s to the same server, for running bulk
+/// operations in parallel
+///
+/// Use [`ConnectionPool::checkout`] to borrow a single connection, or the bulk
+/// methods to split an operation across every connection
+///
+/// ```no_run
+/// # use mcrs::{Connection, ConnectionPool};
+/// let pool = ConnectionPool::new(&Connection::builder(), 4).unwrap();
+/// let chunk = pool.get_blocks([0, 0, 0], [255, 127, 255]).unwrap();
+/// pool.paste(&chunk, [512, 0, 0]).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct ConnectionPool {
+    idle: Mutex<Vec<Connection>>,
+    returned: Condvar,
+    size: usize,
+}
+
+/// A [`Connection`] borrowed from a [`ConnectionPool`], which is returned to
+/// the pool when dropped
+#[derive(Debug)]
+pub struct PooledConnection<'a> {
+    pool: &'a ConnectionPool,
+    connection: Option<Connection>,
+}
+
+impl ConnectionPool {
+    /// Open `size` connections, each configured by a [`ConnectionBuilder`]
+    ///
+    /// A `size` of `0` is rejected with [`io::ErrorKind::InvalidInput`]
+    pub fn new(builder: &ConnectionBuilder, size: usize) -> io::Result<Self> {
+        if size == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "connection pool should have at least 1 connection",
+            ));
+        }
+        let connections = (0..size)
+            .map(|_| builder.connect())
+            .collect::<io::Result<Vec<_>>>()?;
+        Ok(Self {
+            idle: Mutex::new(connections),
+            returned: Condvar::new(),
+            size,
+        })
+    }
+
+    /// Get the amount of connections in the pool
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Borrow a connection, blocking until one is available
+    pub fn checkout(&self) -> PooledConnection<'_> {
+        let mut idle = self.lock();
+        loop {
+            if let Some(connection) = idle.pop() {
+                return PooledConnection {
+                    pool: self,
+                    connection: Some(connection),
+                };
+            }
+            idle = self
+                .returned
+                .wait(idle)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+
+    /// Borrow a connection if one is available, without blocking
+    pub fn try_checkout(&self) -> Option<PooledConnection<'_>> {
+        let connection = self.lock().pop()?;
+        Some(PooledConnection {
+            pool: self,
+            connection: Some(connection),
+        })
+    }
+
+    /// Like [`Connection::get_blocks`], with the cuboid split into slabs
+    /// along its longest axis, which are fetched in parallel
+    pub fn get_blocks(
+        &self,
+        a: impl Into<Coordinate>,
+        b: impl Into<Coordinate>,
+    ) -> io::Result<Chunk> {
+        let a = a.into();
+        let b = b.into();
+        let (min, max) = (a.min(b), a.max(b));
+        let size = min.size_between(max);
+
+        let slabs = split(min, max, self.size);
+        let parts: Vec<io::Result<Chunk>> = thread::scope(|scope| {
+            let handles: Vec<_> = slabs
+                .iter()
+                .map(|(a, b)| scope.spawn(move || self.checkout().get_blocks(*a, *b)))
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("worker thread should not panic"))
+                .collect()
+        });
+
+        let mut list = vec![Block::AIR; size.volume()];
+        for part in parts {
+            for item in part?.iter() {
+                let index = size.coordinate_to_index(item.position_absolute() - min);
+                list[index] = item.block();
+            }
+        }
+        Ok(Chunk::new(min, max, list))
+    }
+
+    /// Like [`Connection::set_blocks`], on any available connection
+    pub fn set_blocks(
+        &self,
+        a: impl Into<Coordinate>,
+        b: impl Into<Coordinate>,
+        block: Block,
+    ) -> io::Result<()> {
+        self.checkout().set_blocks(a, b, block)
+    }
+
+    /// Like [`Connection::paste`], with the blocks split between every
+    /// connection and placed in parallel
+    pub fn paste(&self, chunk: &Chunk, position: impl Into<Coordinate>) -> io::Result<()> {
+        let origin = position.into() - chunk.pivot();
+        let size = chunk.size();
+        let max = Coordinate::new(size.x as i32 - 1, size.y as i32 - 1, size.z as i32 - 1);
+        let slabs = split(Coordinate::new(0, 0, 0), max, self.size);
+
+        thread::scope(|scope| {
+            let handles: Vec<_> = slabs
+                .iter()
+                .map(|(a, b)| {
+                    scope.spawn(move || -> io::Result<()> {
+                        let mut connection = self.checkout();
+                        for x in a.x..=b.x {
+                            for y in a.y..=b.y {
+                                for z in a.z..=b.z {
+                                    let relative = Coordinate::new(x, y, z);
+                                    let block =
+                                        chunk.get(relative).expect("slab should be within chunk");
+                                    connection.set_block(relative + origin, block)?;
+                                }
+                            }
+                        }
+                        Ok(())
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .try_for_each(|handle| handle.join().expect("worker thread should not panic"))
+        })
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<Connection>> {
+        self.idle.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Deref for PooledConnection<'_> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Self::Target {
+        self.connection
+            .as_ref()
+            .expect("connection should be present until dropped")
+    }
+}
+
+impl DerefMut for PooledConnection<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.connection
+            .as_mut()
+            .expect("connection should be present until dropped")
+    }
+}
+
+impl Drop for PooledConnection<'_> {
+    fn drop(&mut self) {
+        if let Some(connection) = self.connection.take() {
+            self.pool.lock().push(connection);
+            self.pool.returned.notify_one();
+        }
+    }
+}
+
+/// Split the cuboid from `min` to `max` into at most `count` slabs along its
+/// longest axis
+///
+/// Returns no slabs if the cuboid is empty, with `max` below `min` on any axis
+fn split(min: Coordinate, max: Coordinate, count: usize) -> Vec<(Coordinate, Coordinate)> {
+    let extents = [(min.x, max.x), (min.y, max.y), (min.z, max.z)]
+        .map(|(min, max)| i64::from(max) - i64::from(min));
+    if extents.iter().any(|extent| *extent < 0) {
+        return Vec::new();
+    }
+    let axis = (0..3)
+        .max_by_key(|axis| extents[*axis])
+        .expect("there should be 3 axes");
+    let length = extents[axis] as usize + 1;
+    let count = count.clamp(1, length);
+
+    let get = |coordinate: Coordinate| [coordinate.x, coordinate.y, coordinate.z][axis];
+    let with = |mut coordinate: Coordinate, value: i32| {
+        match axis {
+            0 => coordinate.x = value,
+            1 => coordinate.y = value,
+            _ => coordinate.z = value,
+        }
+        coordinate
+    };
+
+    // Offsets along the axis can exceed `i32` for very long cuboids
+    let offset = |i: usize| i64::from(get(min)) + (length * i / count) as i64;
+    (0..count)
+        .map(|i| {
+            let start = offset(i) as i32;
+            let end = (offset(i + 1) - 1) as i32;
+            (with(min, start), with(max, end))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Check that the slabs cover the cuboid exactly once, in order
+    fn assert_covers(slabs: &[(Coordinate, Coordinate)], min: Coordinate, max: Coordinate) {
+        let volume = |(a, b): (Coordinate, Coordinate)| a.size_between(b).volume();
+        let total: usize = slabs.iter().map(|slab| volume(*slab)).sum();
+        assert_eq!(total, volume((min, max)));
+        for (a, b) in slabs {
+            assert!(a.min(*b) == *a && a.max(min) == *a && b.min(max) == *b);
+        }
+    }
+
+    #[test]
+    fn split_longest_axis() {
+        let (min, max) = (Coordinate::new(0, 10, -5), Coordinate::new(3, 11, 4));
+        let slabs = split(min, max, 3);
+        assert_eq!(slabs.len(), 3);
+        assert_eq!(slabs[0], (min, Coordinate::new(3, 11, -3)));
+        assert_eq!(
+            slabs[1],
+            (Coordinate::new(0, 10, -2), Coordinate::new(3, 11, 0))
+        );
+        assert_eq!(slabs[2], (Coordinate::new(0, 10, 1), max));
+        assert_covers(&slabs, min, max);
+    }
+
+    #[test]
+    fn split_count_clamped_to_length() {
+        let (min, max) = (Coordinate::new(0, 0, 0), Coordinate::new(1, 0, 0));
+        assert_eq!(split(min, max, 8).len(), 2);
+        assert_eq!(split(min, max, 0), [(min, max)]);
+
+        let single = Coordinate::new(7, 7, 7);
+        assert_eq!(split(single, single, 4), [(single, single)]);
+    }
+
+    #[test]
+    fn split_empty_cuboid() {
+        let min = Coordinate::new(0, 0, 0);
+        assert!(split(min, Coordinate::new(-1, 4, 4), 4).is_empty());
+        assert!(split(min, Coordinate::new(4, 4, -1), 4).is_empty());
+        assert!(split(min, Coordinate::new(-1, -1, -1), 1).is_empty());
+    }
+
+    #[test]
+    fn split_full_range() {
+        let min = Coordinate::new(i32::MIN, 0, 0);
+        let max = Coordinate::new(i32::MAX, 0, 0);
+        let slabs = split(min, max, 2);
+        assert_eq!(slabs[0], (min, Coordinate::new(-1, 0, 0)));
+        assert_eq!(slabs[1], (Coordinate::new(0, 0, 0), max));
+    }
+}
@@ -0,0 +1,76 @@
+use std::{
+    io, thread,
+    time::{Duration, Instant},
+};
+
+use crate::{undo::Entry, Connection};
+
+/// Temporary marker blocks placed by [`Connection::preview`], with the blocks
+/// they replaced
+///
+/// The markers are not removed when dropped, as that requires the
+/// [`Connection`]. Call [`Preview::poll`] regularly, or [`Preview::wait`], to
+/// remove them once expired, or [`Preview::cancel`] to remove them early
+#[derive(Debug)]
+#[must_use = "markers are only removed by `poll`, `wait` or `cancel`"]
+pub struct Preview {
+    /// Writes of each marker, in the order they were placed
+    entries: Vec<Entry>,
+    expires: Instant,
+    restored: bool,
+}
+
+impl Preview {
+    pub(crate) fn new(entries: Vec<Entry>, duration: Duration) -> Self {
+        Self {
+            entries,
+            expires: Instant::now() + duration,
+            restored: false,
+        }
+    }
+
+    /// Returns `true` if the preview has expired, whether or not the original
+    /// blocks have been restored yet
+    pub fn is_expired(&self) -> bool {
+        Instant::now() >= self.expires
+    }
+
+    /// Returns `true` if the original blocks have been restored
+    pub fn is_restored(&self) -> bool {
+        self.restored
+    }
+
+    /// Restore the original blocks if the preview has expired
+    ///
+    /// Returns `true` once the original blocks have been restored
+    pub fn poll(&mut self, mc: &mut Connection) -> io::Result<bool> {
+        if !self.restored && self.is_expired() {
+            self.restore(mc)?;
+        }
+        Ok(self.restored)
+    }
+
+    /// Block until the preview expires, then restore the original blocks
+    pub fn wait(mut self, mc: &mut Connection) -> io::Result<()> {
+        thread::sleep(self.expires.saturating_duration_since(Instant::now()));
+        self.restore(mc)
+    }
+
+    /// Restore the original blocks immediately
+    pub fn cancel(mut self, mc: &mut Connection) -> io::Result<()> {
+        self.restore(mc)
+    }
+
+    /// Write back the original blocks, most recently placed marker first, so
+    /// that overlapping markers are restored correctly
+    fn restore(&mut self, mc: &mut Connection) -> io::Result<()> {
+        if self.restored {
+            return Ok(());
+        }
+        for entry in self.entries.iter().rev() {
+            mc.restore(entry)?;
+        }
+        self.restored = true;
+        Ok(())
+    }
+}
@@ -0,0 +1,17 @@
+/// Which server API a [`Connection`] speaks
+///
+/// [`Connection`]: crate::Connection
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Profile {
+    /// The [ELCI] API (default)
+    ///
+    /// [ELCI]: https://github.com/rozukke/elci
+    #[default]
+    Elci,
+    /// The original Minecraft: Pi Edition API
+    ///
+    /// Bulk block and height queries are emulated with one request per block
+    /// or column, and in-game commands are unsupported. Pi Edition worlds are
+    /// only 256x128x256 blocks, with coordinates relative to the spawn point
+    Pi,
+}
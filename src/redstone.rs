@@ -0,0 +1,226 @@
+//! Each component converts into the [`Block`] with the matching modifier, so
+//! it can be placed with any block placing method, and decodes back with
+//! [`Block::decode`]
+//!
+//! ```
+//! # use mcrs::{block_state::Facing, redstone::Repeater, Block};
+//! let repeater = Repeater {
+//!     output: Facing::East,
+//!     delay: 3,
+//!     powered: false,
+//! };
+//! let block = Block::from(repeater);
+//! assert_eq!(block, Block::new(93, 9));
+//! assert_eq!(block.decode(), Some(repeater));
+//! ```
+
+use crate::{block_state::BlockState, block_state::Facing, Block};
+
+/// A direction along any axis
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Down,
+    North,
+    South,
+    East,
+    West,
+}
+
+/// Redstone dust
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Wire {
+    /// Signal strength from `0` to `15`, recalculated by the server once
+    /// placed
+    pub power: u8,
+}
+
+/// A redstone repeater
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Repeater {
+    /// Direction the signal is sent in
+    pub output: Facing,
+    /// Delay in redstone ticks, from `1` to `4`. Other values are clamped
+    pub delay: u8,
+    pub powered: bool,
+}
+
+/// A redstone comparator
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Comparator {
+    /// Direction the signal is sent in
+    pub output: Facing,
+    /// `true` in subtraction mode, `false` in comparison mode
+    pub subtract: bool,
+    pub powered: bool,
+}
+
+/// A redstone torch
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Torch {
+    /// Direction the torch points away from the wall it is attached to, or
+    /// `None` if standing on the floor
+    pub facing: Option<Facing>,
+    pub lit: bool,
+}
+
+/// A piston or sticky piston
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Piston {
+    /// Direction the piston pushes in
+    pub facing: Direction,
+    pub sticky: bool,
+    pub extended: bool,
+}
+
+impl From<Wire> for Block {
+    fn from(wire: Wire) -> Self {
+        Block::new(Block::REDSTONE_WIRE.id, i32::from(wire.power.min(15)))
+    }
+}
+
+impl From<Repeater> for Block {
+    fn from(repeater: Repeater) -> Self {
+        let id = if repeater.powered { 94 } else { 93 };
+        let delay = repeater.delay.clamp(1, 4) as i32 - 1;
+        Block::new(id, diode_bits(repeater.output) | delay << 2)
+    }
+}
+
+impl From<Comparator> for Block {
+    fn from(comparator: Comparator) -> Self {
+        let id = if comparator.powered { 150 } else { 149 };
+        let modifier = diode_bits(comparator.output)
+            | i32::from(comparator.subtract) << 2
+            | i32::from(comparator.powered) << 3;
+        Block::new(id, modifier)
+    }
+}
+
+impl From<Torch> for Block {
+    fn from(torch: Torch) -> Self {
+        let id = if torch.lit { 76 } else { 75 };
+        let modifier = match torch.facing {
+            Some(Facing::East) => 1,
+            Some(Facing::West) => 2,
+            Some(Facing::South) => 3,
+            Some(Facing::North) => 4,
+            None => 5,
+        };
+        Block::new(id, modifier)
+    }
+}
+
+impl From<Piston> for Block {
+    fn from(piston: Piston) -> Self {
+        let id = if piston.sticky { 29 } else { 33 };
+        let facing = match piston.facing {
+            Direction::Down => 0,
+            Direction::Up => 1,
+            Direction::North => 2,
+            Direction::South => 3,
+            Direction::West => 4,
+            Direction::East => 5,
+        };
+        Block::new(id, facing | i32::from(piston.extended) << 3)
+    }
+}
+
+impl BlockState for Wire {
+    fn decode(block: Block) -> Option<Self> {
+        if block.id != Block::REDSTONE_WIRE.id || !(0..16).contains(&block.modifier) {
+            return None;
+        }
+        Some(Self {
+            power: block.modifier as u8,
+        })
+    }
+}
+
+impl BlockState for Repeater {
+    fn decode(block: Block) -> Option<Self> {
+        if !matches!(block.id, 93 | 94) || !(0..16).contains(&block.modifier) {
+            return None;
+        }
+        Some(Self {
+            output: diode_output(block.modifier),
+            delay: (block.modifier >> 2) as u8 + 1,
+            powered: block.id == 94,
+        })
+    }
+}
+
+impl BlockState for Comparator {
+    fn decode(block: Block) -> Option<Self> {
+        if !matches!(block.id, 149 | 150) || !(0..16).contains(&block.modifier) {
+            return None;
+        }
+        Some(Self {
+            output: diode_output(block.modifier),
+            subtract: block.modifier & 0b100 != 0,
+            powered: block.id == 150 || block.modifier & 0b1000 != 0,
+        })
+    }
+}
+
+impl BlockState for Torch {
+    fn decode(block: Block) -> Option<Self> {
+        if !matches!(block.id, 75 | 76) {
+            return None;
+        }
+        let facing = match block.modifier {
+            1 => Some(Facing::East),
+            2 => Some(Facing::West),
+            3 => Some(Facing::South),
+            4 => Some(Facing::North),
+            5 => None,
+            _ => return None,
+        };
+        Some(Self {
+            facing,
+            lit: block.id == 76,
+        })
+    }
+}
+
+impl BlockState for Piston {
+    fn decode(block: Block) -> Option<Self> {
+        if !matches!(block.id, 29 | 33) {
+            return None;
+        }
+        let facing = match block.modifier & 0b111 {
+            0 => Direction::Down,
+            1 => Direction::Up,
+            2 => Direction::North,
+            3 => Direction::South,
+            4 => Direction::West,
+            5 => Direction::East,
+            _ => return None,
+        };
+        Some(Self {
+            facing,
+            sticky: block.id == 29,
+            extended: block.modifier & 0b1000 != 0,
+        })
+    }
+}
+
+/// Orientation bits of a repeater or comparator, which store the direction
+/// of its input
+fn diode_bits(output: Facing) -> i32 {
+    match output {
+        Facing::North => 0,
+        Facing::East => 1,
+        Facing::South => 2,
+        Facing::West => 3,
+    }
+}
+
+fn diode_output(modifier: i32) -> Facing {
+    match modifier & 0b11 {
+        0 => Facing::North,
+        1 => Facing::East,
+        2 => Facing::South,
+        _ => Facing::West,
+    }
+}
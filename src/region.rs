@@ -0,0 +1,222 @@
+use std::{iter, ops::RangeInclusive};
+
+use crate::Coordinate;
+
+/// A cuboid of blocks, with inclusive bounds on every axis
+///
+/// ```
+/// # use mcrs::Region;
+/// let region = Region::new([0, 64, 0], [4, 68, 4]);
+/// assert_eq!(region.corners().count(), 8);
+/// assert_eq!(region.edges().count(), 44);
+/// assert_eq!(region.faces().count(), 98);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Region {
+    /// Corner with the lowest `x`, `y` and `z` values
+    pub min: Coordinate,
+    /// Corner with the highest `x`, `y` and `z` values
+    pub max: Coordinate,
+}
+
+/// A rectangular area of columns, with inclusive bounds on the `x` and `z` axes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Region2D {
+    /// Corner with the lowest `x` and `z` values, `y`-value is always `0`
+    pub min: Coordinate,
+    /// Corner with the highest `x` and `z` values, `y`-value is always `0`
+    pub max: Coordinate,
+}
+
+/// A polygon on the horizontal plane, optionally with holes
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Polygon {
+    outline: Vec<Coordinate>,
+    holes: Vec<Vec<Coordinate>>,
+}
+
+/// Where [`Connection::polygon_fill`] places blocks vertically
+///
+/// [`Connection::polygon_fill`]: crate::Connection::polygon_fill
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FillHeight {
+    /// At a fixed `y`-value
+    At(i32),
+    /// On top of the terrain surface of each column
+    Surface,
+}
+
+impl Region {
+    /// Create a region from two corner [`Coordinate`]s (in any order)
+    pub fn new(a: impl Into<Coordinate>, b: impl Into<Coordinate>) -> Self {
+        let a = a.into();
+        let b = b.into();
+        Self {
+            min: a.min(b),
+            max: a.max(b),
+        }
+    }
+
+    /// Returns `true` if the **absolute** [`Coordinate`] is within the region
+    pub fn contains(&self, coordinate: impl Into<Coordinate>) -> bool {
+        let coordinate = coordinate.into();
+        self.xs().contains(&coordinate.x)
+            && self.ys().contains(&coordinate.y)
+            && self.zs().contains(&coordinate.z)
+    }
+
+    /// Get the range of `x`-values covered by the region
+    pub fn xs(&self) -> RangeInclusive<i32> {
+        self.min.x..=self.max.x
+    }
+
+    /// Get the range of `y`-values covered by the region
+    pub fn ys(&self) -> RangeInclusive<i32> {
+        self.min.y..=self.max.y
+    }
+
+    /// Get the range of `z`-values covered by the region
+    pub fn zs(&self) -> RangeInclusive<i32> {
+        self.min.z..=self.max.z
+    }
+
+    /// Create an iterator over the [`Coordinate`]s of every block on the
+    /// outer surface of the region, for building a hollow shell
+    pub fn faces(&self) -> impl Iterator<Item = Coordinate> {
+        self.boundary(1)
+    }
+
+    /// Create an iterator over the [`Coordinate`]s of every block on the 12
+    /// edges of the region, for building a frame
+    pub fn edges(&self) -> impl Iterator<Item = Coordinate> {
+        self.boundary(2)
+    }
+
+    /// Create an iterator over the [`Coordinate`]s of the 8 corners of the
+    /// region
+    ///
+    /// Corners which are the same block, if the region is 1 block wide along
+    /// any axis, are only included once
+    pub fn corners(&self) -> impl Iterator<Item = Coordinate> {
+        self.boundary(3)
+    }
+
+    /// Every block which is at the minimum or maximum of at least `axes` axes,
+    /// each included once
+    fn boundary(&self, axes: usize) -> impl Iterator<Item = Coordinate> {
+        let Self { min, max } = *self;
+        self.xs().flat_map(move |x| {
+            (min.y..=max.y).flat_map(move |y| {
+                let on_bounds =
+                    usize::from(x == min.x || x == max.x) + usize::from(y == min.y || y == max.y);
+                // The `z`-values needed for the block to be on enough bounds
+                let zs: Box<dyn Iterator<Item = i32>> = if on_bounds >= axes {
+                    Box::new(min.z..=max.z)
+                } else if on_bounds + 1 == axes {
+                    Box::new(iter::once(min.z).chain((max.z != min.z).then_some(max.z)))
+                } else {
+                    Box::new(iter::empty())
+                };
+                zs.map(move |z| Coordinate::new(x, y, z))
+            })
+        })
+    }
+}
+
+impl Region2D {
+    /// Create a region from two corner [`Coordinate`]s (in any order,
+    /// `y`-values are ignored)
+    pub fn new(a: impl Into<Coordinate>, b: impl Into<Coordinate>) -> Self {
+        let a = a.into();
+        let b = b.into();
+        Self {
+            min: Coordinate::new(a.x.min(b.x), 0, a.z.min(b.z)),
+            max: Coordinate::new(a.x.max(b.x), 0, a.z.max(b.z)),
+        }
+    }
+
+    /// Returns `true` if the column at `x` and `z` is within the region
+    pub fn contains(&self, x: i32, z: i32) -> bool {
+        (self.min.x..=self.max.x).contains(&x) && (self.min.z..=self.max.z).contains(&z)
+    }
+
+    /// Create an iterator over the `y`-agnostic [`Coordinate`]s of every column
+    /// in the region
+    pub fn columns(&self) -> impl Iterator<Item = Coordinate> {
+        let Self { min, max } = *self;
+        (min.x..=max.x).flat_map(move |x| (min.z..=max.z).map(move |z| Coordinate::new(x, 0, z)))
+    }
+}
+
+impl Polygon {
+    /// Create a polygon from its vertices in order (`y`-values are ignored)
+    pub fn new<T: Into<Coordinate>>(vertices: impl IntoIterator<Item = T>) -> Self {
+        Self {
+            outline: flatten(vertices),
+            holes: Vec::new(),
+        }
+    }
+
+    /// Cut a hole out of the polygon, specified by its vertices in order
+    /// (`y`-values are ignored)
+    pub fn with_hole<T: Into<Coordinate>>(mut self, vertices: impl IntoIterator<Item = T>) -> Self {
+        self.holes.push(flatten(vertices));
+        self
+    }
+
+    /// Get the smallest [`Region2D`] containing the polygon, if it has any
+    /// vertices
+    pub fn bounds(&self) -> Option<Region2D> {
+        let first = *self.outline.first()?;
+        let (min, max) = self
+            .outline
+            .iter()
+            .fold((first, first), |(min, max), vertex| {
+                (min.min(*vertex), max.max(*vertex))
+            });
+        Some(Region2D { min, max })
+    }
+
+    /// Returns `true` if the center of the column at `x` and `z` is inside the
+    /// polygon and not inside any of its holes
+    pub fn contains(&self, x: i32, z: i32) -> bool {
+        let point = (x as f64 + 0.5, z as f64 + 0.5);
+        ring_contains(&self.outline, point)
+            && !self.holes.iter().any(|hole| ring_contains(hole, point))
+    }
+
+    /// Get the `y`-agnostic [`Coordinate`]s of every column inside the polygon
+    pub fn columns(&self) -> Vec<Coordinate> {
+        let Some(bounds) = self.bounds() else {
+            return Vec::new();
+        };
+        bounds
+            .columns()
+            .filter(|column| self.contains(column.x, column.z))
+            .collect()
+    }
+}
+
+fn flatten<T: Into<Coordinate>>(vertices: impl IntoIterator<Item = T>) -> Vec<Coordinate> {
+    vertices
+        .into_iter()
+        .map(|vertex| {
+            let vertex = vertex.into();
+            Coordinate::new(vertex.x, 0, vertex.z)
+        })
+        .collect()
+}
+
+/// Even-odd test of whether a point is inside a closed ring of vertices
+fn ring_contains(ring: &[Coordinate], (x, z): (f64, f64)) -> bool {
+    let mut inside = false;
+    for (i, a) in ring.iter().enumerate() {
+        let b = ring[(i + 1) % ring.len()];
+        let (ax, az) = (a.x as f64, a.z as f64);
+        let (bx, bz) = (b.x as f64, b.z as f64);
+        if (az > z) != (bz > z) && x < ax + (z - az) / (bz - az) * (bx - ax) {
+            inside = !inside;
+        }
+    }
+    inside
+}
@@ -2,58 +2,114 @@ use std::str::Split;
 
 use crate::{Block, Coordinate};
 
+/// How to treat unexpected data in a server response
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ParseMode {
+    /// Any unexpected data makes the response malformed (default)
+    #[default]
+    Strict,
+    /// Extra trailing values and empty list items are ignored
+    Lenient,
+}
+
+/// A line sent by the server in response to a command
+///
+/// Read with [`Connection::recv_raw`] after [`Connection::send_raw`]
+///
+/// [`Connection::recv_raw`]: crate::Connection::recv_raw
+/// [`Connection::send_raw`]: crate::Connection::send_raw
 #[derive(Debug)]
 pub struct Response {
     response: String,
+    mode: ParseMode,
 }
 
 impl Response {
-    pub fn new(response: String) -> Self {
-        Self { response }
+    /// Create a response from a line, with any `\n` or `\r\n` line ending
+    pub(crate) fn new(mut response: String, mode: ParseMode) -> Self {
+        let len = response.trim_end_matches(['\r', '\n']).len();
+        response.truncate(len);
+        Self { response, mode }
     }
 
+    /// Get the response line, without its line ending
+    pub fn as_str(&self) -> &str {
+        &self.response
+    }
+
+    /// Parse the response as a single integer, flooring decimal values, or
+    /// `None` if it is out of range of `i32`
     pub fn as_integer(&self) -> Option<i32> {
-        self.response.trim().parse().ok()
+        match self.mode {
+            ParseMode::Strict => i32::try_from(self.as_i64()?).ok(),
+            ParseMode::Lenient => IntegerList::from(&self.response).next(),
+        }
     }
 
+    /// Parse the response as a single 64-bit integer, flooring decimal values
+    pub fn as_i64(&self) -> Option<i64> {
+        let mut list = IntegerList::from(&self.response);
+        let value = list.next_i64()?;
+        if self.mode == ParseMode::Strict && list.inner.next().is_some() {
+            return None;
+        }
+        Some(value)
+    }
+
+    /// Parse the response as a single unsigned integer, flooring decimal
+    /// values, or `None` if it is out of range of `u32`
+    pub fn as_u32(&self) -> Option<u32> {
+        u32::try_from(self.as_i64()?).ok()
+    }
+
+    /// Parse the response as a comma-separated [`Coordinate`], flooring
+    /// decimal values
     pub fn as_coordinate(&self) -> Option<Coordinate> {
-        parse_coord(&self.response)
+        parse_coord(&self.response, self.mode)
     }
 
+    /// Parse the response as a comma-separated [`Block`] id and modifier
     pub fn as_block(&self) -> Option<Block> {
-        parse_block(&self.response)
+        parse_block(&self.response, self.mode)
     }
 
+    /// Parse the response as comma-separated integers, stopping at the first
+    /// malformed value
     pub fn as_integer_list(&self) -> Vec<i32> {
         IntegerList::from(&self.response).collect()
     }
 
+    /// Parse the response as semicolon-separated [`Block`]s, or `None` if
+    /// any is malformed
     pub fn as_block_list(&self) -> Option<Vec<Block>> {
         let mut list = Vec::new();
         for item in self.response.split(';') {
-            let block = parse_block(item)?;
+            if self.mode == ParseMode::Lenient && item.trim().is_empty() {
+                continue;
+            }
+            let block = parse_block(item, self.mode)?;
             list.push(block);
         }
         Some(list)
     }
 }
 
-fn parse_coord(item: &str) -> Option<Coordinate> {
+fn parse_coord(item: &str, mode: ParseMode) -> Option<Coordinate> {
     let mut iter = IntegerList::from(item);
     let x = iter.next()?;
     let y = iter.next()?;
     let z = iter.next()?;
-    if iter.next().is_some() {
+    if mode == ParseMode::Strict && iter.next().is_some() {
         return None;
     }
     Some(Coordinate { x, y, z })
 }
 
-fn parse_block(item: &str) -> Option<Block> {
+fn parse_block(item: &str, mode: ParseMode) -> Option<Block> {
     let mut iter = IntegerList::from(item);
     let id = iter.next()?;
     let modifier = iter.next()?;
-    if iter.next().is_some() {
+    if mode == ParseMode::Strict && iter.next().is_some() {
         return None;
     }
     Some(Block { id, modifier })
@@ -69,14 +125,107 @@ impl<'a> IntegerList<'a> {
             inner: line.split(','),
         }
     }
+
+    /// Parse the next item as a 64-bit integer, flooring decimal values
+    ///
+    /// Integers are parsed exactly, rather than through a float. Decimal
+    /// values may use scientific notation (eg. `1.0E7` or `-2.5e-3`), which
+    /// some server forks emit for large doubles
+    fn next_i64(&mut self) -> Option<i64> {
+        let item = self.inner.next()?.trim();
+        if let Ok(int) = item.parse() {
+            return Some(int);
+        }
+        let float: f64 = item.parse().ok()?;
+        if !float.is_finite() {
+            return None;
+        }
+        Some(float.floor() as i64)
+    }
 }
 
 impl Iterator for IntegerList<'_> {
     type Item = i32;
 
+    /// Values out of range of `i32` end the list, as if malformed
     fn next(&mut self) -> Option<Self::Item> {
-        let item = self.inner.next()?;
-        let float: f32 = item.trim().parse().ok()?;
-        Some(float.floor() as i32)
+        i32::try_from(self.next_i64()?).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strict(line: &str) -> Response {
+        Response::new(line.to_string(), ParseMode::Strict)
+    }
+
+    fn lenient(line: &str) -> Response {
+        Response::new(line.to_string(), ParseMode::Lenient)
+    }
+
+    #[test]
+    fn as_i64() {
+        assert_eq!(strict("12").as_i64(), Some(12));
+        assert_eq!(strict(" -7 ").as_i64(), Some(-7));
+        assert_eq!(
+            strict("9007199254740993").as_i64(),
+            Some(9_007_199_254_740_993)
+        );
+        assert_eq!(strict("3000000000").as_i64(), Some(3_000_000_000));
+        assert_eq!(strict("-1.5").as_i64(), Some(-2));
+        assert_eq!(strict("1,2").as_i64(), None);
+        assert_eq!(lenient("1,2").as_i64(), Some(1));
+        assert_eq!(strict("").as_i64(), None);
+        assert_eq!(strict("Fail").as_i64(), None);
+    }
+
+    #[test]
+    fn as_u32() {
+        assert_eq!(strict("0").as_u32(), Some(0));
+        assert_eq!(strict("4294967295").as_u32(), Some(u32::MAX));
+        assert_eq!(strict("4294967296").as_u32(), None);
+        assert_eq!(strict("-1").as_u32(), None);
+    }
+
+    #[test]
+    fn scientific_notation() {
+        assert_eq!(strict("1.0E7").as_integer(), Some(10_000_000));
+        assert_eq!(strict("1.0E7").as_i64(), Some(10_000_000));
+        assert_eq!(strict("-2.5e-3").as_i64(), Some(-1));
+        assert_eq!(
+            strict("1.0E7,64.5,-2.5e-3").as_coordinate(),
+            Some(Coordinate::new(10_000_000, 64, -1)),
+        );
+    }
+
+    #[test]
+    fn out_of_range_ends_list() {
+        assert_eq!(strict("1,2147483648,3").as_integer_list(), [1]);
+        assert_eq!(strict("2147483648").as_integer(), None);
+        assert_eq!(strict("-2147483649").as_integer_list(), []);
+        assert_eq!(
+            strict("2147483647,-2147483648").as_integer_list(),
+            [i32::MAX, i32::MIN]
+        );
+        assert_eq!(strict("3.0E9,0,0").as_coordinate(), None);
+    }
+
+    #[test]
+    fn non_finite_rejected() {
+        for item in ["inf", "-inf", "infinity", "NaN", "1e400"] {
+            assert_eq!(strict(item).as_i64(), None, "{item}");
+            assert_eq!(strict(&format!("1,{item},3")).as_integer_list(), [1]);
+        }
+    }
+
+    #[test]
+    fn line_ending_trimmed() {
+        let response = strict("1,2\r\n");
+        assert_eq!(response.as_str(), "1,2");
+        assert_eq!(response.as_block(), Some(Block::new(1, 2)));
+        assert_eq!(strict("5\n").as_i64(), Some(5));
+        assert_eq!(strict("\r\n").as_str(), "");
     }
 }
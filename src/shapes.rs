@@ -0,0 +1,264 @@
+//! Each shape is given as the **absolute** [`Coordinate`]s of the blocks it
+//! covers, which can be passed to any block placing method
+//!
+//! ```no_run
+//! # use mcrs::{shapes, Block, Connection, Coordinate};
+//! let mut mc = Connection::new().unwrap();
+//! let center = Coordinate::new(0, 80, 0);
+//! for location in shapes::ellipsoid(center, [8, 4, 8], true) {
+//!     mc.set_block(location, Block::GLASS).unwrap();
+//! }
+//! ```
+
+use std::collections::HashSet;
+
+use crate::{coordinate::NEIGHBORS, Coordinate};
+
+/// An ellipsoid with the given radius along each axis
+///
+/// If `hollow` is `true`, only the outer shell is included
+pub fn ellipsoid(
+    center: impl Into<Coordinate>,
+    radii: impl Into<Coordinate>,
+    hollow: bool,
+) -> impl Iterator<Item = Coordinate> {
+    let center = center.into();
+    let radii = radii.into();
+    let [rx, ry, rz] = [radii.x, radii.y, radii.z].map(|radius| radius.max(0) as f64 + 0.5);
+    shape(center - radii, center + radii, hollow, move |point| {
+        let offset = point - center;
+        let (x, y, z) = (
+            offset.x as f64 / rx,
+            offset.y as f64 / ry,
+            offset.z as f64 / rz,
+        );
+        x * x + y * y + z * z <= 1.0
+    })
+}
+
+/// A horizontal torus (ring), with `major` radius from the center to the middle
+/// of the tube, and `minor` radius of the tube
+///
+/// If `hollow` is `true`, only the outer shell is included
+pub fn torus(
+    center: impl Into<Coordinate>,
+    major: u32,
+    minor: u32,
+    hollow: bool,
+) -> impl Iterator<Item = Coordinate> {
+    let center = center.into();
+    let extent = (major + minor) as i32;
+    let minor_i32 = minor as i32;
+    let major = major as f64;
+    let minor = minor as f64 + 0.5;
+    shape(
+        center - [extent, minor_i32, extent],
+        center + [extent, minor_i32, extent],
+        hollow,
+        move |point| {
+            let offset = point - center;
+            let (x, y, z) = (offset.x as f64, offset.y as f64, offset.z as f64);
+            let ring = (x * x + z * z).sqrt() - major;
+            ring * ring + y * y <= minor * minor
+        },
+    )
+}
+
+/// An upright cone with its circular base centered on `base` and its tip
+/// `height` blocks above
+///
+/// If `hollow` is `true`, only the outer shell is included
+pub fn cone(
+    base: impl Into<Coordinate>,
+    radius: u32,
+    height: u32,
+    hollow: bool,
+) -> impl Iterator<Item = Coordinate> {
+    upright(base, radius, height, hollow, |progress| 1.0 - progress)
+}
+
+/// An upright paraboloid (dome shape) with its circular base centered on `base`
+/// and its peak `height` blocks above
+///
+/// If `hollow` is `true`, only the outer shell is included
+pub fn paraboloid(
+    base: impl Into<Coordinate>,
+    radius: u32,
+    height: u32,
+    hollow: bool,
+) -> impl Iterator<Item = Coordinate> {
+    upright(base, radius, height, hollow, |progress| {
+        (1.0 - progress).sqrt()
+    })
+}
+
+/// An upright shape of revolution, where `profile` gives the radius (as a
+/// fraction of the base radius) at a fraction of the height
+fn upright(
+    base: impl Into<Coordinate>,
+    radius: u32,
+    height: u32,
+    hollow: bool,
+    profile: impl Fn(f64) -> f64,
+) -> impl Iterator<Item = Coordinate> {
+    let base = base.into();
+    let radius_i32 = radius as i32;
+    let radius = radius as f64 + 0.5;
+    shape(
+        base - [radius_i32, 0, radius_i32],
+        base + [radius_i32, height as i32, radius_i32],
+        hollow,
+        move |point| {
+            let offset = point - base;
+            if !(0..=height as i32).contains(&offset.y) {
+                return false;
+            }
+            let progress = offset.y as f64 / (height as f64 + 1.0);
+            let (x, z) = (offset.x as f64, offset.z as f64);
+            let allowed = radius * profile(progress);
+            x * x + z * z <= allowed * allowed
+        },
+    )
+}
+
+/// Every point within the inclusive bounds for which `inside` holds, or only
+/// those with a face-adjacent point outside if `hollow` is `true`
+fn shape(
+    min: Coordinate,
+    max: Coordinate,
+    hollow: bool,
+    inside: impl Fn(Coordinate) -> bool,
+) -> impl Iterator<Item = Coordinate> {
+    (min.y..=max.y)
+        .flat_map(move |y| {
+            (min.x..=max.x)
+                .flat_map(move |x| (min.z..=max.z).map(move |z| Coordinate::new(x, y, z)))
+        })
+        .filter(move |point| {
+            if !inside(*point) {
+                return false;
+            }
+            if !hollow {
+                return true;
+            }
+            NEIGHBORS.iter().any(|offset| !inside(*point + *offset))
+        })
+}
+
+/// A smooth curve passing through every point in `points`, as a continuous
+/// line of blocks
+///
+/// Uses a Catmull-Rom spline, with the end points repeated so the curve
+/// reaches them
+pub fn catmull_rom(points: &[Coordinate]) -> Vec<Coordinate> {
+    let mut samples = Vec::new();
+    for i in 0..points.len().saturating_sub(1) {
+        let p0 = to_f64(points[i.saturating_sub(1)]);
+        let p1 = to_f64(points[i]);
+        let p2 = to_f64(points[i + 1]);
+        let p3 = to_f64(points[(i + 2).min(points.len() - 1)]);
+        let steps = segment_steps(points[i], points[i + 1]);
+        for step in 0..steps {
+            let t = step as f64 / steps as f64;
+            let (t2, t3) = (t * t, t * t * t);
+            samples.push(std::array::from_fn(|axis| {
+                0.5 * (2.0 * p1[axis]
+                    + (p2[axis] - p0[axis]) * t
+                    + (2.0 * p0[axis] - 5.0 * p1[axis] + 4.0 * p2[axis] - p3[axis]) * t2
+                    + (3.0 * p1[axis] - p0[axis] - 3.0 * p2[axis] + p3[axis]) * t3)
+            }));
+        }
+    }
+    samples.extend(points.last().copied().map(to_f64));
+    connect(samples)
+}
+
+/// A smooth Bézier curve starting at the first and ending at the last of
+/// `controls`, pulled towards the others, as a continuous line of blocks
+pub fn bezier(controls: &[Coordinate]) -> Vec<Coordinate> {
+    if controls.is_empty() {
+        return Vec::new();
+    }
+    let points: Vec<[f64; 3]> = controls.iter().copied().map(to_f64).collect();
+    let length: u32 = controls
+        .windows(2)
+        .map(|pair| segment_steps(pair[0], pair[1]))
+        .sum();
+    let steps = length.max(1);
+    let samples = (0..=steps).map(|step| {
+        // De Casteljau's algorithm
+        let t = step as f64 / steps as f64;
+        let mut points = points.clone();
+        for level in (1..points.len()).rev() {
+            for i in 0..level {
+                points[i] = std::array::from_fn(|axis| {
+                    points[i][axis] + (points[i + 1][axis] - points[i][axis]) * t
+                });
+            }
+        }
+        points[0]
+    });
+    connect(samples.collect())
+}
+
+/// Sweep a 2D `profile` along a `path` (such as from [`catmull_rom`] or
+/// [`bezier`]), giving the blocks of the resulting solid
+///
+/// Each profile offset is `(side, up)`: `side` is horizontal and perpendicular
+/// to the direction of the path (positive to the right), `up` is vertical. For
+/// example, `[(-1, 0), (0, 0), (1, 0)]` gives a 3 block wide road
+pub fn extrude(path: &[Coordinate], profile: &[(i32, i32)]) -> Vec<Coordinate> {
+    let mut seen = HashSet::new();
+    let mut blocks = Vec::new();
+    for (i, point) in path.iter().enumerate() {
+        let previous = path[i.saturating_sub(1)];
+        let next = path[(i + 1).min(path.len() - 1)];
+        let direction = next - previous;
+        // Snap the horizontal direction to the nearest axis, and turn right
+        let right = if direction.x.abs() >= direction.z.abs() {
+            Coordinate::new(0, 0, direction.x.signum())
+        } else {
+            Coordinate::new(-direction.z.signum(), 0, 0)
+        };
+        for (side, up) in profile {
+            let block = *point + [right.x * side, *up, right.z * side];
+            if seen.insert(block) {
+                blocks.push(block);
+            }
+        }
+    }
+    blocks
+}
+
+fn to_f64(coordinate: Coordinate) -> [f64; 3] {
+    [
+        coordinate.x as f64,
+        coordinate.y as f64,
+        coordinate.z as f64,
+    ]
+}
+
+/// Amount of samples to take between two points, enough to not skip blocks
+fn segment_steps(a: Coordinate, b: Coordinate) -> u32 {
+    let delta = b - a;
+    let length = delta.x.unsigned_abs() + delta.y.unsigned_abs() + delta.z.unsigned_abs();
+    length.max(1) * 2
+}
+
+/// Round curve samples to blocks, filling any gaps with straight lines
+fn connect(samples: Vec<[f64; 3]>) -> Vec<Coordinate> {
+    let mut blocks: Vec<Coordinate> = Vec::new();
+    for sample in samples {
+        let [x, y, z] = sample.map(|value| value.round() as i32);
+        let block = Coordinate::new(x, y, z);
+        match blocks.last() {
+            Some(last) if *last == block => {}
+            Some(last) => {
+                let line = last.line_to(block);
+                blocks.extend_from_slice(&line[1..]);
+            }
+            None => blocks.push(block),
+        }
+    }
+    blocks
+}
@@ -0,0 +1,410 @@
+use std::collections::HashMap;
+
+use crate::{Block, Chunk, Coordinate};
+
+/// Width of the cubic cells blocks are grouped into for spatial queries
+const CELL_SIZE: i32 = 16;
+
+/// Scattered blocks stored by **absolute** [`Coordinate`], grouped into a grid
+/// of cells for efficient range and nearest-neighbor queries
+///
+/// Uses far less memory than a [`Chunk`] when blocks are spread over a large
+/// area
+#[derive(Clone, Debug, Default)]
+pub struct SparseWorld {
+    blocks: HashMap<Coordinate, Block>,
+    cells: HashMap<Coordinate, Vec<Coordinate>>,
+}
+
+impl SparseWorld {
+    /// Create an empty world
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a world from every block of a [`Chunk`] which is not air
+    pub fn from_chunk(chunk: &Chunk) -> Self {
+        let mut world = Self::new();
+        for item in chunk.iter() {
+            if item.block() != Block::AIR {
+                world.insert(item.position_absolute(), item.block());
+            }
+        }
+        world
+    }
+
+    /// Create a [`Chunk`] covering every stored block, with air everywhere
+    /// else, or `None` if the world is empty
+    ///
+    /// The chunk stores every block within the [`SparseWorld::bounds`], so may
+    /// be very large if the blocks are far apart
+    pub fn to_chunk(&self) -> Option<Chunk> {
+        let (min, max) = self.bounds()?;
+        let size = min.size_between(max);
+        let mut list = vec![Block::AIR; size.volume()];
+        for (coordinate, block) in &self.blocks {
+            list[size.coordinate_to_index(*coordinate - min)] = *block;
+        }
+        Some(Chunk::new(min, max, list))
+    }
+
+    /// Store a [`Block`] at the **absolute** [`Coordinate`], returning the
+    /// previous block if any
+    pub fn insert(&mut self, coordinate: impl Into<Coordinate>, block: Block) -> Option<Block> {
+        let coordinate = coordinate.into();
+        let previous = self.blocks.insert(coordinate, block);
+        if previous.is_none() {
+            self.cells
+                .entry(cell_of(coordinate))
+                .or_default()
+                .push(coordinate);
+        }
+        previous
+    }
+
+    /// Remove the [`Block`] at the **absolute** [`Coordinate`], returning it
+    /// if any
+    pub fn remove(&mut self, coordinate: impl Into<Coordinate>) -> Option<Block> {
+        let coordinate = coordinate.into();
+        let block = self.blocks.remove(&coordinate)?;
+        let cell = cell_of(coordinate);
+        if let Some(members) = self.cells.get_mut(&cell) {
+            members.retain(|member| *member != coordinate);
+            if members.is_empty() {
+                self.cells.remove(&cell);
+            }
+        }
+        Some(block)
+    }
+
+    /// Get the [`Block`] at the **absolute** [`Coordinate`], if stored
+    pub fn get(&self, coordinate: impl Into<Coordinate>) -> Option<Block> {
+        self.blocks.get(&coordinate.into()).copied()
+    }
+
+    /// Get the amount of stored blocks
+    pub fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Returns `true` if no blocks are stored
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+
+    /// Create an iterator over every stored block, in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = (Coordinate, Block)> + '_ {
+        self.blocks
+            .iter()
+            .map(|(coordinate, block)| (*coordinate, *block))
+    }
+
+    /// Get the smallest cuboid containing every stored block, as its minimum
+    /// and maximum corners, or `None` if the world is empty
+    pub fn bounds(&self) -> Option<(Coordinate, Coordinate)> {
+        let mut coordinates = self.blocks.keys();
+        let first = *coordinates.next()?;
+        Some(coordinates.fold((first, first), |(min, max), coordinate| {
+            (min.min(*coordinate), max.max(*coordinate))
+        }))
+    }
+
+    /// Find every stored block within the cuboid specified by [`Coordinate`]s
+    /// `a` and `b` (in any order), in no particular order
+    pub fn range(
+        &self,
+        a: impl Into<Coordinate>,
+        b: impl Into<Coordinate>,
+    ) -> Vec<(Coordinate, Block)> {
+        let a = a.into();
+        let b = b.into();
+        let (min, max) = (a.min(b), a.max(b));
+        let (min_cell, max_cell) = (cell_of(min), cell_of(max));
+
+        let mut found = Vec::new();
+        let mut visit = |cell: &Coordinate, members: &Vec<Coordinate>| {
+            let in_range = (min_cell.x..=max_cell.x).contains(&cell.x)
+                && (min_cell.y..=max_cell.y).contains(&cell.y)
+                && (min_cell.z..=max_cell.z).contains(&cell.z);
+            if !in_range {
+                return;
+            }
+            for coordinate in members {
+                let inside = (min.x..=max.x).contains(&coordinate.x)
+                    && (min.y..=max.y).contains(&coordinate.y)
+                    && (min.z..=max.z).contains(&coordinate.z);
+                if inside {
+                    found.push((*coordinate, self.blocks[coordinate]));
+                }
+            }
+        };
+
+        // Visit whichever is fewer: the cells in range, or the occupied cells
+        let size = min_cell.size_between(max_cell);
+        if size
+            .checked_volume()
+            .is_some_and(|volume| volume <= self.cells.len())
+        {
+            for x in min_cell.x..=max_cell.x {
+                for y in min_cell.y..=max_cell.y {
+                    for z in min_cell.z..=max_cell.z {
+                        let cell = Coordinate::new(x, y, z);
+                        if let Some(members) = self.cells.get(&cell) {
+                            visit(&cell, members);
+                        }
+                    }
+                }
+            }
+        } else {
+            for (cell, members) in &self.cells {
+                visit(cell, members);
+            }
+        }
+        found
+    }
+
+    /// Find the stored block closest to the **absolute** [`Coordinate`] by
+    /// straight-line distance, or `None` if the world is empty
+    ///
+    /// Ties are broken arbitrarily
+    pub fn nearest(&self, coordinate: impl Into<Coordinate>) -> Option<(Coordinate, Block)> {
+        self.nearest_matching(coordinate, |_| true)
+    }
+
+    /// Find the stored block closest to the **absolute** [`Coordinate`] by
+    /// straight-line distance, for which `predicate` returns `true`
+    ///
+    /// Ties are broken arbitrarily
+    pub fn nearest_matching(
+        &self,
+        coordinate: impl Into<Coordinate>,
+        predicate: impl Fn(Block) -> bool,
+    ) -> Option<(Coordinate, Block)> {
+        let target = coordinate.into();
+        let center = cell_of(target);
+
+        // Furthest ring of cells which could contain a block
+        let max_ring = self
+            .cells
+            .keys()
+            .map(|cell| {
+                let offset = *cell - center;
+                offset.x.abs().max(offset.y.abs()).max(offset.z.abs())
+            })
+            .max()?;
+
+        let mut best: Option<(i128, Coordinate)> = None;
+        for ring in 0..=max_ring {
+            // Once a ring has more cells than are occupied, checking every
+            // occupied cell is cheaper
+            let side = i64::from(ring) * 2 + 1;
+            if side.pow(3) - (side - 2).max(0).pow(3) > self.cells.len() as i64 {
+                for members in self.cells.values() {
+                    closest(&mut best, members, target, |member| {
+                        predicate(self.blocks[member])
+                    });
+                }
+                break;
+            }
+            for members in ring_cells(center, ring).filter_map(|cell| self.cells.get(&cell)) {
+                closest(&mut best, members, target, |member| {
+                    predicate(self.blocks[member])
+                });
+            }
+            // Any block in a further ring is at least this far away
+            let reach = i128::from(ring) * i128::from(CELL_SIZE);
+            if best.is_some_and(|(best, _)| best <= reach * reach) {
+                break;
+            }
+        }
+        best.map(|(_, coordinate)| (coordinate, self.blocks[&coordinate]))
+    }
+}
+
+impl FromIterator<(Coordinate, Block)> for SparseWorld {
+    fn from_iter<T: IntoIterator<Item = (Coordinate, Block)>>(iter: T) -> Self {
+        let mut world = Self::new();
+        for (coordinate, block) in iter {
+            world.insert(coordinate, block);
+        }
+        world
+    }
+}
+
+fn cell_of(coordinate: Coordinate) -> Coordinate {
+    Coordinate::new(
+        coordinate.x.div_euclid(CELL_SIZE),
+        coordinate.y.div_euclid(CELL_SIZE),
+        coordinate.z.div_euclid(CELL_SIZE),
+    )
+}
+
+/// Cells on the surface of the cube of cells `ring` cells away from `center`
+fn ring_cells(center: Coordinate, ring: i32) -> impl Iterator<Item = Coordinate> {
+    (-ring..=ring).flat_map(move |x| {
+        (-ring..=ring).flat_map(move |y| {
+            (-ring..=ring).filter_map(move |z| {
+                let on_surface = x.abs() == ring || y.abs() == ring || z.abs() == ring;
+                on_surface.then(|| center + [x, y, z])
+            })
+        })
+    })
+}
+
+/// Replace `best` with any member closer to `target`, for which `predicate`
+/// returns `true`
+fn closest(
+    best: &mut Option<(i128, Coordinate)>,
+    members: &[Coordinate],
+    target: Coordinate,
+    predicate: impl Fn(&Coordinate) -> bool,
+) {
+    for member in members.iter().filter(|member| predicate(member)) {
+        let distance = distance_squared(target, *member);
+        if best.is_none_or(|(best, _)| distance < best) {
+            *best = Some((distance, *member));
+        }
+    }
+}
+
+/// Squared distance between two coordinates, which cannot overflow for any
+/// pair of coordinates
+fn distance_squared(a: Coordinate, b: Coordinate) -> i128 {
+    [(a.x, b.x), (a.y, b.y), (a.z, b.z)]
+        .iter()
+        .map(|(a, b)| (i128::from(*a) - i128::from(*b)).pow(2))
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic pseudo-random coordinates within `-range..range`
+    fn scatter(count: usize, range: i32) -> Vec<Coordinate> {
+        let mut state = 0x2545_f491_u32;
+        let mut next = move || {
+            state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            (state >> 8) as i32 % range
+        };
+        (0..count)
+            .map(|_| Coordinate::new(next(), next(), next()))
+            .collect()
+    }
+
+    fn brute_force(
+        world: &SparseWorld,
+        target: Coordinate,
+        predicate: impl Fn(Block) -> bool,
+    ) -> Option<i128> {
+        world
+            .iter()
+            .filter(|(_, block)| predicate(*block))
+            .map(|(coordinate, _)| distance_squared(target, coordinate))
+            .min()
+    }
+
+    #[test]
+    fn empty_world() {
+        let world = SparseWorld::new();
+        assert!(world.nearest([0, 0, 0]).is_none());
+        assert!(world.bounds().is_none());
+        assert!(world.to_chunk().is_none());
+        assert!(world.range([-100, -100, -100], [100, 100, 100]).is_empty());
+    }
+
+    #[test]
+    fn nearest_in_adjacent_cell() {
+        // The block in the same cell is further than the one across the border
+        let mut world = SparseWorld::new();
+        world.insert([0, 0, 0], Block::STONE);
+        world.insert([17, 0, 0], Block::DIRT);
+        assert_eq!(
+            world.nearest([15, 0, 0]),
+            Some(([17, 0, 0].into(), Block::DIRT))
+        );
+        assert_eq!(
+            world.nearest([-1, 0, 0]),
+            Some(([0, 0, 0].into(), Block::STONE))
+        );
+    }
+
+    #[test]
+    fn nearest_matching_skips_rejected() {
+        let mut world = SparseWorld::new();
+        world.insert([1, 0, 0], Block::STONE);
+        world.insert([40, 0, 0], Block::GOLD_ORE);
+        let found = world.nearest_matching([0, 0, 0], |block| block == Block::GOLD_ORE);
+        assert_eq!(found, Some(([40, 0, 0].into(), Block::GOLD_ORE)));
+        assert!(world
+            .nearest_matching([0, 0, 0], |block| block == Block::GLASS)
+            .is_none());
+    }
+
+    #[test]
+    fn nearest_matches_brute_force() {
+        let blocks = [Block::STONE, Block::DIRT, Block::GLASS];
+        for (count, range) in [(1, 10), (40, 300), (500, 100), (300, 2000)] {
+            let world: SparseWorld = scatter(count, range)
+                .into_iter()
+                .enumerate()
+                .map(|(i, coordinate)| (coordinate, blocks[i % blocks.len()]))
+                .collect();
+            for target in scatter(50, range * 2) {
+                let found = world.nearest(target).unwrap();
+                assert_eq!(
+                    Some(distance_squared(target, found.0)),
+                    brute_force(&world, target, |_| true),
+                    "{target:?}",
+                );
+                let is_glass = |block| block == Block::GLASS;
+                let found = world.nearest_matching(target, is_glass);
+                assert_eq!(
+                    found.map(|(coordinate, _)| distance_squared(target, coordinate)),
+                    brute_force(&world, target, is_glass),
+                    "{target:?}",
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn extreme_coordinates() {
+        let min = Coordinate::new(i32::MIN, i32::MIN, i32::MIN);
+        let max = Coordinate::new(i32::MAX, i32::MAX, i32::MAX);
+        let mut world = SparseWorld::new();
+        world.insert(min, Block::STONE);
+        world.insert(max, Block::DIRT);
+        assert_eq!(world.nearest(max - [1, 1, 1]), Some((max, Block::DIRT)));
+        assert!(world.nearest([0, 0, 0]).is_some());
+        assert_eq!(world.range(min, max).len(), 2);
+        assert_eq!(world.range(min, [0, 0, 0]), [(min, Block::STONE)]);
+    }
+
+    #[test]
+    fn insert_and_remove() {
+        let mut world = SparseWorld::new();
+        assert_eq!(world.insert([3, 4, 5], Block::STONE), None);
+        assert_eq!(world.insert([3, 4, 5], Block::DIRT), Some(Block::STONE));
+        assert_eq!(world.len(), 1);
+        assert_eq!(world.remove([3, 4, 5]), Some(Block::DIRT));
+        assert_eq!(world.remove([3, 4, 5]), None);
+        assert!(world.is_empty());
+        assert!(world.nearest([3, 4, 5]).is_none());
+    }
+
+    #[test]
+    fn chunk_round_trip() {
+        let mut world = SparseWorld::new();
+        world.insert([-2, 64, 3], Block::STONE);
+        world.insert([1, 66, 3], Block::GLASS);
+        let chunk = world.to_chunk().unwrap();
+        assert_eq!(chunk.origin(), Coordinate::new(-2, 64, 3));
+        assert_eq!(chunk.get([3, 2, 0]), Some(Block::GLASS));
+        assert_eq!(chunk.get([1, 0, 0]), Some(Block::AIR));
+
+        let round_trip = SparseWorld::from_chunk(&chunk);
+        assert_eq!(round_trip.len(), 2);
+        assert_eq!(round_trip.get([1, 66, 3]), Some(Block::GLASS));
+    }
+}
@@ -0,0 +1,103 @@
+use std::io;
+
+use crate::{Block, Connection, Coordinate};
+
+/// Most differences written to `dst` in one write
+const WRITE_BATCH: usize = 1024;
+
+/// Copies the cuboid specified by [`Coordinate`]s `a` and `b` (in any order)
+/// from `src` to `dst`, only writing blocks which differ
+///
+/// Differences are written in batches of up to 1024 blocks, each sent in one
+/// write (see [`Connection::set_blocks_at`]). `progress` is called after each
+/// batch with the amount of blocks handled so far and the total amount of
+/// differences. If `dry_run` is `true`, nothing is written to `dst`, but
+/// `progress` is still called for each batch
+///
+/// Returns the **absolute** [`Coordinate`] and new [`Block`] of every
+/// difference
+pub fn sync_region(
+    src: &mut Connection,
+    dst: &mut Connection,
+    a: impl Into<Coordinate>,
+    b: impl Into<Coordinate>,
+    dry_run: bool,
+    mut progress: impl FnMut(usize, usize),
+) -> io::Result<Vec<(Coordinate, Block)>> {
+    let a = a.into();
+    let b = b.into();
+    let source = src.get_blocks(a, b)?;
+    let target = dst.get_blocks(a, b)?;
+
+    let differences: Vec<_> = source
+        .iter()
+        .zip(target.iter())
+        .filter(|(source, target)| source.block() != target.block())
+        .map(|(source, _)| (source.position_absolute(), source.block()))
+        .collect();
+
+    let total = differences.len();
+    let mut handled = 0;
+    for batch in differences.chunks(WRITE_BATCH) {
+        if !dry_run {
+            dst.set_blocks_at(batch.iter().copied())?;
+        }
+        handled += batch.len();
+        progress(handled, total);
+    }
+
+    Ok(differences)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockConnection;
+
+    fn connections() -> (MockConnection, MockConnection) {
+        let mut src = MockConnection::new();
+        let mut dst = MockConnection::new();
+        src.push_response("1,0;20,0;3,0");
+        dst.push_response("1,0;0,0;0,0");
+        (src, dst)
+    }
+
+    #[test]
+    fn writes_differences_in_one_batch() {
+        let (mut src, mut dst) = connections();
+        let mut reports = Vec::new();
+        let differences = sync_region(
+            &mut src,
+            &mut dst,
+            [0, 0, 0],
+            [0, 0, 2],
+            false,
+            |done, total| reports.push((done, total)),
+        )
+        .unwrap();
+        assert_eq!(differences.len(), 2);
+        assert_eq!(reports, [(2, 2)]);
+        assert_eq!(
+            &dst.sent_commands()[1..],
+            ["world.setBlock(0,0,1,20,0)", "world.setBlock(0,0,2,3,0)"],
+        );
+    }
+
+    #[test]
+    fn dry_run_reports_progress() {
+        let (mut src, mut dst) = connections();
+        let mut reports = Vec::new();
+        let differences = sync_region(
+            &mut src,
+            &mut dst,
+            [0, 0, 0],
+            [0, 0, 2],
+            true,
+            |done, total| reports.push((done, total)),
+        )
+        .unwrap();
+        assert_eq!(differences.len(), 2);
+        assert_eq!(reports, [(2, 2)]);
+        assert_eq!(dst.sent_commands().len(), 1);
+    }
+}
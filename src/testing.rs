@@ -0,0 +1,156 @@
+//! A [`MockConnection`](crate::testing::MockConnection) behaves like a
+//! [`Connection`](crate::Connection) without a server, recording the commands
+//! sent and answering with scripted responses
+//!
+//! ```
+//! # use mcrs::{testing::MockConnection, Block};
+//! let mut mc = MockConnection::new();
+//! mc.push_response("1,0");
+//! assert_eq!(mc.get_block([0, 64, 0]).unwrap(), Block::STONE);
+//! mc.set_block([0, 65, 0], Block::GLASS).unwrap();
+//! assert_eq!(
+//!     mc.sent_commands(),
+//!     ["world.getBlockWithData(0,64,0)", "world.setBlock(0,65,0,20,0)"],
+//! );
+//! ```
+
+use std::{
+    collections::VecDeque,
+    io::{self, Read, Write},
+    ops::{Deref, DerefMut},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use crate::{Connection, Transport};
+
+/// A [`Connection`] which records every command sent, and answers each
+/// request with the next scripted response
+///
+/// Dereferences to [`Connection`], so every method is available. A request
+/// with no scripted response left fails with
+/// [`io::ErrorKind::UnexpectedEof`]
+#[derive(Debug)]
+pub struct MockConnection {
+    connection: Connection,
+    script: Arc<Mutex<Script>>,
+}
+
+/// State shared between a [`MockConnection`] and its transport
+#[derive(Debug, Default)]
+struct Script {
+    sent: Vec<u8>,
+    responses: VecDeque<u8>,
+}
+
+/// The [`Transport`] of a [`MockConnection`]
+#[derive(Debug)]
+struct MockTransport {
+    script: Arc<Mutex<Script>>,
+}
+
+impl MockConnection {
+    /// Create a mock connection with no scripted responses
+    pub fn new() -> Self {
+        let script = Arc::new(Mutex::new(Script::default()));
+        let transport = MockTransport {
+            script: Arc::clone(&script),
+        };
+        Self {
+            connection: Connection::from_transport(transport),
+            script,
+        }
+    }
+
+    /// Queue a response line, such as `"2,0"` for a grass block, to answer
+    /// the next unanswered request
+    pub fn push_response(&mut self, response: impl AsRef<str>) {
+        let mut script = self.lock();
+        script.responses.extend(response.as_ref().bytes());
+        script.responses.push_back(b'\n');
+    }
+
+    /// Returns every command sent so far, without line endings
+    pub fn sent_commands(&self) -> Vec<String> {
+        String::from_utf8_lossy(&self.lock().sent)
+            .lines()
+            .map(String::from)
+            .collect()
+    }
+
+    /// Forget every command sent so far
+    pub fn clear_sent(&mut self) {
+        self.lock().sent.clear();
+    }
+
+    /// Returns the amount of scripted responses not yet read
+    pub fn pending_responses(&self) -> usize {
+        self.lock()
+            .responses
+            .iter()
+            .filter(|byte| **byte == b'\n')
+            .count()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Script> {
+        self.script.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Default for MockConnection {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deref for MockConnection {
+    type Target = Connection;
+
+    fn deref(&self) -> &Self::Target {
+        &self.connection
+    }
+}
+
+impl DerefMut for MockConnection {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.connection
+    }
+}
+
+impl Read for MockTransport {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut script = self.script.lock().unwrap_or_else(PoisonError::into_inner);
+        if script.responses.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "no scripted response left",
+            ));
+        }
+        // Yield at most one line, so unread responses stay in the script
+        let mut count = 0;
+        while count < buf.len() {
+            let Some(byte) = script.responses.pop_front() else {
+                break;
+            };
+            buf[count] = byte;
+            count += 1;
+            if byte == b'\n' {
+                break;
+            }
+        }
+        Ok(count)
+    }
+}
+
+impl Write for MockTransport {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut script = self.script.lock().unwrap_or_else(PoisonError::into_inner);
+        script.sent.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Transport for MockTransport {}
@@ -0,0 +1,21 @@
+use std::time::Duration;
+
+/// Statistics about a completed bulk operation
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Timing {
+    /// Amount of items (blocks or heights) transferred
+    pub items: usize,
+    /// Wall time taken by the operation, including waiting for the server
+    pub duration: Duration,
+}
+
+impl Timing {
+    /// Get the average amount of items transferred per second
+    pub fn items_per_second(&self) -> f64 {
+        let seconds = self.duration.as_secs_f64();
+        if seconds == 0.0 {
+            return 0.0;
+        }
+        self.items as f64 / seconds
+    }
+}
@@ -0,0 +1,82 @@
+use std::{
+    fmt::Debug,
+    io::{self, Read, Write},
+    net::TcpStream,
+    time::Duration,
+};
+
+/// A byte stream which a [`Connection`] speaks the line protocol over
+///
+/// Implemented for [`TcpStream`] and, on Unix, `UnixStream`. Implement it for
+/// any other stream (in-memory pipes, TLS wrappers, tunnels) and connect with
+/// [`Connection::from_transport`]
+///
+/// ```no_run
+/// # use std::net::TcpStream;
+/// # use mcrs::Connection;
+/// let stream = TcpStream::connect("127.0.0.1:4711").unwrap();
+/// let mut mc = Connection::from_transport(stream);
+/// ```
+///
+/// [`Connection`]: crate::Connection
+/// [`Connection::from_transport`]: crate::Connection::from_transport
+pub trait Transport: Read + Write + Send + Debug {
+    /// Sets how long a read may block before failing, or `None` to wait
+    /// indefinitely
+    ///
+    /// Fails with [`io::ErrorKind::Unsupported`] unless implemented
+    fn set_read_timeout(&mut self, timeout: Option<Duration>) -> io::Result<()> {
+        let _ = timeout;
+        Err(unsupported("read timeouts"))
+    }
+
+    /// Sets how long a write may block before failing, or `None` to wait
+    /// indefinitely
+    ///
+    /// Fails with [`io::ErrorKind::Unsupported`] unless implemented
+    fn set_write_timeout(&mut self, timeout: Option<Duration>) -> io::Result<()> {
+        let _ = timeout;
+        Err(unsupported("write timeouts"))
+    }
+
+    /// Sets whether small writes are sent immediately, rather than combined
+    /// into fewer packets (Nagle's algorithm)
+    ///
+    /// Fails with [`io::ErrorKind::Unsupported`] unless implemented
+    fn set_nodelay(&mut self, nodelay: bool) -> io::Result<()> {
+        let _ = nodelay;
+        Err(unsupported("delay settings"))
+    }
+}
+
+impl Transport for TcpStream {
+    fn set_read_timeout(&mut self, timeout: Option<Duration>) -> io::Result<()> {
+        TcpStream::set_read_timeout(self, timeout)
+    }
+
+    fn set_write_timeout(&mut self, timeout: Option<Duration>) -> io::Result<()> {
+        TcpStream::set_write_timeout(self, timeout)
+    }
+
+    fn set_nodelay(&mut self, nodelay: bool) -> io::Result<()> {
+        TcpStream::set_nodelay(self, nodelay)
+    }
+}
+
+#[cfg(unix)]
+impl Transport for std::os::unix::net::UnixStream {
+    fn set_read_timeout(&mut self, timeout: Option<Duration>) -> io::Result<()> {
+        std::os::unix::net::UnixStream::set_read_timeout(self, timeout)
+    }
+
+    fn set_write_timeout(&mut self, timeout: Option<Duration>) -> io::Result<()> {
+        std::os::unix::net::UnixStream::set_write_timeout(self, timeout)
+    }
+}
+
+fn unsupported(feature: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!("{feature} are not supported by this transport"),
+    )
+}
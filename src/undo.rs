@@ -0,0 +1,85 @@
+use std::collections::VecDeque;
+
+use crate::{Block, Chunk, Coordinate};
+
+/// History of block writes made through a [`Connection`], with the prior state
+/// of each write
+///
+/// [`Connection`]: crate::Connection
+#[derive(Clone, Debug)]
+pub(crate) struct UndoHistory {
+    undo: VecDeque<Entry>,
+    redo: Vec<Entry>,
+    /// Maximum amount of prior blocks to store across all undo entries
+    limit: usize,
+    stored: usize,
+}
+
+/// A cuboid which was set to a single [`Block`], with its prior contents
+#[derive(Clone, Debug)]
+pub(crate) struct Entry {
+    pub before: Chunk,
+    pub block: Block,
+}
+
+impl Entry {
+    /// Get the corners of the cuboid which was written to
+    pub fn corners(&self) -> (Coordinate, Coordinate) {
+        let min = self.before.origin();
+        let size = self.before.size();
+        let max = min + [size.x as i32 - 1, size.y as i32 - 1, size.z as i32 - 1];
+        (min, max)
+    }
+
+    fn len(&self) -> usize {
+        self.before.size().volume()
+    }
+}
+
+impl UndoHistory {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            limit,
+            stored: 0,
+        }
+    }
+
+    /// Record a new write, discarding any undone writes and dropping the
+    /// oldest entries if over the limit
+    pub fn push(&mut self, entry: Entry) {
+        self.redo.clear();
+        self.push_undo(entry);
+    }
+
+    /// Take the most recent write to be undone
+    pub fn pop_undo(&mut self) -> Option<Entry> {
+        let entry = self.undo.pop_back()?;
+        self.stored -= entry.len();
+        Some(entry)
+    }
+
+    /// Record a write which was undone, so it can be redone
+    pub fn push_redo(&mut self, entry: Entry) {
+        self.redo.push(entry);
+    }
+
+    /// Take the most recently undone write to be redone
+    pub fn pop_redo(&mut self) -> Option<Entry> {
+        self.redo.pop()
+    }
+
+    /// Record a write which was redone, without discarding other undone
+    /// writes
+    pub fn push_undo(&mut self, entry: Entry) {
+        self.stored += entry.len();
+        self.undo.push_back(entry);
+        while self.stored > self.limit {
+            let Some(oldest) = self.undo.pop_front() else {
+                break;
+            };
+            self.stored -= oldest.len();
+        }
+    }
+}
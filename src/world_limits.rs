@@ -0,0 +1,53 @@
+use std::{error, fmt};
+
+/// Vertical bounds of a Minecraft world, both inclusive
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WorldLimits {
+    /// Lowest `y`-value blocks can be placed at
+    pub min_y: i32,
+    /// Highest `y`-value blocks can be placed at
+    pub max_y: i32,
+}
+
+impl WorldLimits {
+    /// Limits of worlds before Minecraft 1.18
+    pub const LEGACY: Self = Self::new(0, 255);
+    /// Limits of overworlds from Minecraft 1.18 onwards
+    pub const MODERN: Self = Self::new(-64, 319);
+
+    /// Create new world limits
+    pub const fn new(min_y: i32, max_y: i32) -> Self {
+        Self { min_y, max_y }
+    }
+
+    /// Returns `true` if the `y`-value is within the limits
+    pub const fn contains(&self, y: i32) -> bool {
+        self.min_y <= y && y <= self.max_y
+    }
+}
+
+/// Error for an operation reaching outside of the configured [`WorldLimits`]
+///
+/// Returned wrapped in an [`io::Error`] of kind [`InvalidInput`]
+///
+/// [`io::Error`]: std::io::Error
+/// [`InvalidInput`]: std::io::ErrorKind::InvalidInput
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OutOfWorld {
+    /// The `y`-value which was out of bounds
+    pub y: i32,
+    /// The limits which were exceeded
+    pub limits: WorldLimits,
+}
+
+impl fmt::Display for OutOfWorld {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "y-value {} is outside of the world ({} to {})",
+            self.y, self.limits.min_y, self.limits.max_y
+        )
+    }
+}
+
+impl error::Error for OutOfWorld {}